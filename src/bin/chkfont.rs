@@ -0,0 +1,43 @@
+//! check FIGlet and TOIlet fonts like figlet's `chkfont`
+//!
+//! Usage: `chkfont FONTFILE...`, exits with 1 when a font has errors.
+
+use figlet_rs::{check_font_file, Severity};
+use std::env;
+use std::process;
+
+fn main() {
+    let filenames: Vec<String> = env::args().skip(1).collect();
+    if filenames.is_empty() {
+        eprintln!("usage: chkfont FONTFILE...");
+        process::exit(2);
+    }
+
+    let mut failed = false;
+    for filename in &filenames {
+        match check_font_file(filename) {
+            Ok(diagnostics) => {
+                for diagnostic in &diagnostics {
+                    println!("{filename}: {diagnostic}");
+                }
+                let errors = diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.severity == Severity::Error)
+                    .count();
+                println!(
+                    "{filename}: {errors} errors, {} warnings",
+                    diagnostics.len() - errors
+                );
+                failed |= errors > 0;
+            }
+            Err(e) => {
+                println!("{filename}: {e}");
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::error::Error;
+use crate::figlet::FIGlet;
+use crate::font::Font;
+use crate::shared::{
+    FIGcharacter, FontKind, HeaderLine, Layout, PrintDirection, SM_KERN, SM_SMUSH,
+};
+use crate::toilet::Toilet;
+use crate::writer::{codetag_characters, write_header_line};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// builds a font glyph by glyph, starting from an empty font or an existing one
+///
+/// Every FIGcharacter is checked against the height of the font when it is inserted, and the
+/// header line is regenerated by `build_figlet` and `build_toilet`, so the result is always a
+/// consistent font.
+///
+/// ```
+/// use figlet_rs::FontBuilder;
+///
+/// let mut builder = FontBuilder::new(2, 2).unwrap();
+/// builder.comments("a tiny font");
+/// builder.insert('H' as u32, "|_|\n| |").unwrap();
+/// builder.insert('I' as u32, "|\n|").unwrap();
+/// let font = builder.build_figlet();
+/// assert_eq!("|_||\n| ||\n", font.convert("HI").unwrap().as_str());
+/// ```
+#[derive(Debug, Clone)]
+pub struct FontBuilder {
+    header_line: HeaderLine,
+    comments: String,
+    fonts: BTreeMap<u32, FIGcharacter>,
+}
+
+impl FontBuilder {
+    /// an empty font with `$` as hardblank and fitting as layout
+    pub fn new(height: u32, baseline: u32) -> Result<FontBuilder, Error> {
+        if height == 0 || baseline == 0 || baseline > height {
+            return Err(Error::Header(format!(
+                "baseline {baseline} must be between 1 and height {height}"
+            )));
+        }
+
+        let height = i32::try_from(height)
+            .map_err(|_| Error::Header(format!("height {height} is too large")))?;
+        Ok(FontBuilder {
+            header_line: HeaderLine {
+                header_line: String::new(),
+                signature: "flf2a".to_string(),
+                hardblank: '$',
+                height,
+                baseline: baseline as i32,
+                max_length: 2,
+                old_layout: 0,
+                comment_lines: 0,
+                print_direction: Some(0),
+                full_layout: Some(SM_KERN),
+                codetag_count: None,
+            },
+            comments: String::new(),
+            fonts: BTreeMap::new(),
+        })
+    }
+
+    /// start from the header, comments and FIGcharacters of a font
+    pub fn from_figlet(font: &FIGlet) -> FontBuilder {
+        FontBuilder {
+            header_line: font.header_line.clone(),
+            comments: font.comments.clone(),
+            fonts: font.fonts.clone(),
+        }
+    }
+
+    /// start from the header, comments and FIGcharacters of a font
+    pub fn from_toilet(font: &Toilet) -> FontBuilder {
+        FontBuilder {
+            header_line: font.header_line.clone(),
+            comments: font.comments.clone(),
+            fonts: font.fonts.clone(),
+        }
+    }
+
+    /// start from the header, comments and FIGcharacters of a font
+    pub fn from_font(font: &Font) -> FontBuilder {
+        FontBuilder {
+            header_line: font.header_line().clone(),
+            comments: font.comments().to_string(),
+            fonts: font.fonts().clone(),
+        }
+    }
+
+    /// replace the comments, lines are separated by `\n`
+    pub fn comments(&mut self, comments: &str) -> &mut Self {
+        self.comments = comments.to_string();
+        self
+    }
+
+    /// the sub-character which is rendered as a blank but never smushed
+    pub fn hardblank(&mut self, hardblank: char) -> &mut Self {
+        self.header_line.hardblank = hardblank;
+        self
+    }
+
+    /// the horizontal layout of the font, `Layout::Default` keeps the current one
+    pub fn layout(&mut self, layout: Layout) -> &mut Self {
+        let (old_layout, horizontal) = match layout {
+            Layout::Default => return self,
+            Layout::FullWidth => (-1, 0),
+            Layout::Fitting => (0, SM_KERN),
+            Layout::UniversalSmushing => (0, SM_SMUSH),
+            Layout::ControlledSmushing(rules) => (rules.bits(), rules.bits() | SM_SMUSH),
+        };
+
+        let vertical = self.header_line.vertical_layout();
+        self.header_line.old_layout = old_layout;
+        self.header_line.full_layout = Some(vertical | horizontal);
+        self
+    }
+
+    /// the print direction of the font, `PrintDirection::Default` keeps the current one
+    pub fn direction(&mut self, direction: PrintDirection) -> &mut Self {
+        match direction {
+            PrintDirection::Default => {}
+            PrintDirection::LeftToRight => self.header_line.print_direction = Some(0),
+            PrintDirection::RightToLeft => self.header_line.print_direction = Some(1),
+        }
+        self
+    }
+
+    /// add or replace the FIGcharacter for `code`, and return the one it replaces
+    ///
+    /// Every line of `art` is a row of the FIGcharacter, there must be as many rows as the font
+    /// is high. Shorter rows are padded with blanks on the right. The codetag name of a replaced
+    /// FIGcharacter is kept.
+    pub fn insert(&mut self, code: u32, art: &str) -> Result<Option<FIGcharacter>, Error> {
+        let mut rows: Vec<String> = art
+            .split('\n')
+            .map(|row| row.strip_suffix('\r').unwrap_or(row).to_string())
+            .collect();
+        if rows.len() != self.header_line.height as usize {
+            return Err(Error::InvalidGlyph {
+                code,
+                message: format!(
+                    "FIGcharacter has {} rows, the font is {} rows high",
+                    rows.len(),
+                    self.header_line.height
+                ),
+            });
+        }
+
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        for row in &mut rows {
+            let padding = width - row.chars().count();
+            row.extend(core::iter::repeat(' ').take(padding));
+        }
+
+        let character = FIGcharacter {
+            code,
+            characters: rows,
+            width: width as u32,
+            height: self.header_line.height as u32,
+            name: self.fonts.get(&code).and_then(|font| font.name.clone()),
+        };
+        Ok(self.fonts.insert(code, character))
+    }
+
+    /// remove the FIGcharacter for `code`
+    pub fn remove(&mut self, code: u32) -> Option<FIGcharacter> {
+        self.fonts.remove(&code)
+    }
+
+    /// the FIGcharacter for `code`
+    pub fn get(&self, code: u32) -> Option<&FIGcharacter> {
+        self.fonts.get(&code)
+    }
+
+    /// a FIGlet font with the `flf2a` signature
+    pub fn build_figlet(&self) -> FIGlet {
+        let (header_line, comments, fonts) = self.finish("flf2a");
+        FIGlet {
+            header_line,
+            comments,
+            fonts,
+        }
+    }
+
+    /// a Toilet font with the `tlf2a` signature
+    pub fn build_toilet(&self) -> Toilet {
+        let (header_line, comments, fonts) = self.finish("tlf2a");
+        Toilet {
+            header_line,
+            comments,
+            fonts,
+        }
+    }
+
+    /// a FIGlet or Toilet font, whichever `kind` asks for
+    pub fn build(&self, kind: FontKind) -> Font {
+        match kind {
+            FontKind::FIGlet => Font::FIGlet(self.build_figlet()),
+            FontKind::Toilet => Font::Toilet(self.build_toilet()),
+        }
+    }
+
+    fn finish(&self, signature: &str) -> (HeaderLine, String, BTreeMap<u32, FIGcharacter>) {
+        let mut header_line = self.header_line.clone();
+        header_line.signature = signature.to_string();
+
+        let comment_lines = if self.comments.is_empty() {
+            0
+        } else {
+            self.comments.split('\n').count()
+        };
+        let codetag_count = codetag_characters(&self.fonts).len();
+        // the widest row plus two endmarks
+        let max_length = self
+            .fonts
+            .values()
+            .map(|font| font.width as i32 + 2)
+            .max()
+            .unwrap_or(2);
+
+        header_line.comment_lines = comment_lines as i32;
+        header_line.max_length = max_length;
+        if header_line.codetag_count.is_some() || codetag_count > 0 {
+            header_line.codetag_count = Some(codetag_count as i32);
+        }
+        header_line.header_line = write_header_line(&header_line, comment_lines, codetag_count);
+
+        (header_line, self.comments.clone(), self.fonts.clone())
+    }
+}
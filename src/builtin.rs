@@ -0,0 +1,106 @@
+use crate::error::Error;
+use crate::font::Font;
+use crate::shared::FontKind;
+
+/// a font bundled with the crate, the same fonts the constructors like `FIGlet::standard` load
+///
+/// `Mono12` and `Mono9` are zip-packaged, so they are only available with the `std` feature.
+///
+/// ```
+/// use figlet_rs::{BuiltinFont, FIGlet, FontKind};
+///
+/// let builtin = BuiltinFont::from_name("slant").unwrap();
+/// assert_eq!(FontKind::FIGlet, builtin.kind());
+/// let font = FIGlet::from_bytes(builtin.bytes()).unwrap();
+/// assert!(font.convert("Test").is_some());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BuiltinFont {
+    Standard,
+    Small,
+    Big,
+    Slant,
+    Smblock,
+    #[cfg(feature = "std")]
+    Mono12,
+    Future,
+    Wideterm,
+    #[cfg(feature = "std")]
+    Mono9,
+}
+
+impl BuiltinFont {
+    /// every font enabled in this build, FIGlet fonts first
+    pub const ALL: &'static [BuiltinFont] = &[
+        BuiltinFont::Standard,
+        BuiltinFont::Small,
+        BuiltinFont::Big,
+        BuiltinFont::Slant,
+        BuiltinFont::Smblock,
+        #[cfg(feature = "std")]
+        BuiltinFont::Mono12,
+        BuiltinFont::Future,
+        BuiltinFont::Wideterm,
+        #[cfg(feature = "std")]
+        BuiltinFont::Mono9,
+    ];
+
+    /// find a font by the name figlet or toilet knows it as, e.g. `standard` or `mono12`
+    pub fn from_name(name: &str) -> Option<BuiltinFont> {
+        BuiltinFont::ALL
+            .iter()
+            .copied()
+            .find(|font| font.name() == name)
+    }
+
+    /// the name of the font file without suffix
+    pub fn name(self) -> &'static str {
+        match self {
+            BuiltinFont::Standard => "standard",
+            BuiltinFont::Small => "small",
+            BuiltinFont::Big => "big",
+            BuiltinFont::Slant => "slant",
+            BuiltinFont::Smblock => "smblock",
+            #[cfg(feature = "std")]
+            BuiltinFont::Mono12 => "mono12",
+            BuiltinFont::Future => "future",
+            BuiltinFont::Wideterm => "wideterm",
+            #[cfg(feature = "std")]
+            BuiltinFont::Mono9 => "mono9",
+        }
+    }
+
+    pub fn kind(self) -> FontKind {
+        match self {
+            BuiltinFont::Standard | BuiltinFont::Small | BuiltinFont::Big | BuiltinFont::Slant => {
+                FontKind::FIGlet
+            }
+            BuiltinFont::Smblock | BuiltinFont::Future | BuiltinFont::Wideterm => FontKind::Toilet,
+            #[cfg(feature = "std")]
+            BuiltinFont::Mono12 | BuiltinFont::Mono9 => FontKind::Toilet,
+        }
+    }
+
+    /// the font file, some toilet fonts are zip-packaged
+    pub fn bytes(self) -> &'static [u8] {
+        match self {
+            BuiltinFont::Standard => include_bytes!("../resources/standard.flf"),
+            BuiltinFont::Small => include_bytes!("../resources/small.flf"),
+            BuiltinFont::Big => include_bytes!("../resources/big.flf"),
+            BuiltinFont::Slant => include_bytes!("../resources/slant.flf"),
+            BuiltinFont::Smblock => include_bytes!("../resources/smblock.tlf"),
+            #[cfg(feature = "std")]
+            BuiltinFont::Mono12 => include_bytes!("../resources/mono12.tlf"),
+            BuiltinFont::Future => include_bytes!("../resources/future.tlf"),
+            BuiltinFont::Wideterm => include_bytes!("../resources/wideterm.tlf"),
+            #[cfg(feature = "std")]
+            BuiltinFont::Mono9 => include_bytes!("../resources/mono9.tlf"),
+        }
+    }
+
+    /// parse the font
+    pub fn load(self) -> Result<Font, Error> {
+        Font::from_bytes_of_kind(self.bytes(), self.kind())
+    }
+}
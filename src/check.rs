@@ -0,0 +1,422 @@
+use crate::error::Error;
+use crate::shared::{decode_font_bytes, extract_codetag_font_code, HeaderLine, REQUIRED_CODES};
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::fs;
+
+/// how serious a problem found by `check_font` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// the font loads, but doesn't follow the usual conventions
+    Warning,
+    /// the font breaks the FIGfont 2.2 spec
+    Error,
+}
+
+/// a problem found by `check_font`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// line of the font file starting at 1, `None` for problems of the whole font
+    pub line: Option<usize>,
+    /// the FIGcharacter the problem belongs to
+    pub code: Option<u32>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// a problem lenient parsing skipped
+    pub(crate) fn warning(error: Error) -> Self {
+        let (line, code, message) = match error {
+            Error::Glyph {
+                line,
+                code,
+                message,
+            } => (Some(line), Some(code), message),
+            Error::Codetag { line, message } => (Some(line), None, message),
+            e => (None, None, e.to_string()),
+        };
+
+        Diagnostic {
+            severity: Severity::Warning,
+            line,
+            code,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}: ")?;
+        }
+        match self.severity {
+            Severity::Warning => write!(f, "warning: ")?,
+            Severity::Error => write!(f, "error: ")?,
+        }
+        if let Some(code) = self.code {
+            write!(f, "FIGcharacter {code} (U+{code:04X}): ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// check a font the way figlet's `chkfont` does, and report every problem instead of stopping at
+/// the first one. An empty list means the font is fine.
+///
+/// ```
+/// use figlet_rs::{check_font, Severity};
+///
+/// let diagnostics = check_font("flf2a$ 1 2 3 -1 0\n");
+/// assert!(diagnostics.iter().any(|d| d.severity == Severity::Error));
+/// ```
+pub fn check_font(contents: &str) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut checker = Checker::default();
+
+    let Some(first) = lines.first() else {
+        checker.error(None, None, "font is empty".to_string());
+        return checker.diagnostics;
+    };
+    let header_line = match HeaderLine::try_from(*first) {
+        Ok(header_line) => header_line,
+        Err(e) => {
+            checker.error(Some(0), None, e.to_string());
+            return checker.diagnostics;
+        }
+    };
+
+    checker.check_header_line(&header_line);
+    if header_line.height < 1 || header_line.comment_lines < 0 {
+        return checker.diagnostics;
+    }
+
+    let height = header_line.height as usize;
+    let mut index = 1 + header_line.comment_lines as usize;
+    if index > lines.len() {
+        checker.error(
+            Some(0),
+            None,
+            format!(
+                "header declares {} comment lines, the font has only {}",
+                header_line.comment_lines,
+                lines.len() - 1
+            ),
+        );
+        return checker.diagnostics;
+    }
+
+    for code in REQUIRED_CODES {
+        if index + height > lines.len() {
+            let message = if code < 127 {
+                "missing required FIGcharacter"
+            } else {
+                "missing required Deutsch FIGcharacter"
+            };
+            checker.error(None, Some(code), message.to_string());
+            continue;
+        }
+
+        checker.check_character(&lines, index, height, Some(code), &header_line);
+        index += height;
+    }
+
+    let mut codetag_count = 0;
+    let mut codes = BTreeSet::new();
+    while index < lines.len() {
+        if index + 1 + height > lines.len() {
+            checker.error(
+                Some(index),
+                None,
+                format!(
+                    "{} trailing lines don't make up a FIGcharacter of height {height}",
+                    lines.len() - index
+                ),
+            );
+            break;
+        }
+
+        codetag_count += 1;
+        match extract_codetag_font_code(&lines, index) {
+            Ok(Some(code)) => {
+                if !codes.insert(code) {
+                    checker.warning(
+                        Some(index),
+                        Some(code),
+                        "codetag is defined twice".to_string(),
+                    );
+                }
+                checker.check_character(&lines, index + 1, height, Some(code), &header_line);
+            }
+            Ok(None) => {
+                if lines[index].split_whitespace().next() == Some("-1") {
+                    checker.error(Some(index), None, "code -1 is not allowed".to_string());
+                }
+                checker.check_character(&lines, index + 1, height, None, &header_line);
+            }
+            Err(Error::Codetag { message, .. }) => checker.error(Some(index), None, message),
+            Err(e) => checker.error(Some(index), None, e.to_string()),
+        }
+        index += 1 + height;
+    }
+
+    if let Some(count) = header_line.codetag_count {
+        // many fonts made with the toilet tools declare 0 instead of the real count
+        if count == 0 && codetag_count > 0 {
+            checker.warning(
+                Some(0),
+                None,
+                format!("codetag_count is 0, but the font has {codetag_count} codetags"),
+            );
+        } else if count != codetag_count {
+            checker.error(
+                Some(0),
+                None,
+                format!("codetag_count is {count}, but the font has {codetag_count} codetags"),
+            );
+        }
+    }
+
+    let longest = checker.longest_row;
+    if longest > 0 && header_line.max_length > longest as i32 {
+        checker.warning(
+            Some(0),
+            None,
+            format!(
+                "max_length is {}, but the longest line is only {longest} characters",
+                header_line.max_length
+            ),
+        );
+    }
+
+    checker.diagnostics
+}
+
+/// check a plain or zip-packaged font, see `check_font`. Decoding errors are returned as `Err`.
+pub fn check_font_bytes(bytes: &[u8]) -> Result<Vec<Diagnostic>, Error> {
+    let contents = decode_font_bytes(bytes, None)?;
+    Ok(check_font(&contents))
+}
+
+/// check a font file, see `check_font`
+#[cfg(feature = "std")]
+pub fn check_font_file(filename: &str) -> Result<Vec<Diagnostic>, Error> {
+    check_font_bytes(&fs::read(filename)?)
+}
+
+#[derive(Default)]
+struct Checker {
+    diagnostics: Vec<Diagnostic>,
+    longest_row: usize,
+}
+
+impl Checker {
+    fn error(&mut self, index: Option<usize>, code: Option<u32>, message: String) {
+        self.push(Severity::Error, index, code, message);
+    }
+
+    fn warning(&mut self, index: Option<usize>, code: Option<u32>, message: String) {
+        self.push(Severity::Warning, index, code, message);
+    }
+
+    /// `index` is the 0-based index of the line
+    fn push(
+        &mut self,
+        severity: Severity,
+        index: Option<usize>,
+        code: Option<u32>,
+        message: String,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            line: index.map(|index| index + 1),
+            code,
+            message,
+        });
+    }
+
+    fn check_header_line(&mut self, header_line: &HeaderLine) {
+        if header_line.signature != "flf2a" && header_line.signature != "tlf2a" {
+            self.error(
+                Some(0),
+                None,
+                format!("unknown signature {:?}", header_line.signature),
+            );
+        }
+        if matches!(header_line.hardblank, ' ' | '\r' | '\n' | '\0') {
+            self.error(
+                Some(0),
+                None,
+                format!("hardblank {:?} is not allowed", header_line.hardblank),
+            );
+        }
+        if header_line.height < 1 {
+            self.error(
+                Some(0),
+                None,
+                format!("height {} is less than 1", header_line.height),
+            );
+        }
+        if header_line.baseline < 1 || header_line.baseline > header_line.height {
+            self.error(
+                Some(0),
+                None,
+                format!(
+                    "baseline {} is not between 1 and height {}",
+                    header_line.baseline, header_line.height
+                ),
+            );
+        }
+        if header_line.max_length < 1 {
+            self.error(
+                Some(0),
+                None,
+                format!("max_length {} is less than 1", header_line.max_length),
+            );
+        }
+        if !(-1..=63).contains(&header_line.old_layout) {
+            self.error(
+                Some(0),
+                None,
+                format!(
+                    "old_layout {} is not between -1 and 63",
+                    header_line.old_layout
+                ),
+            );
+        }
+        if header_line.comment_lines < 0 {
+            self.error(
+                Some(0),
+                None,
+                format!("comment_lines {} is negative", header_line.comment_lines),
+            );
+        }
+        if let Some(direction) = header_line.print_direction {
+            if direction != 0 && direction != 1 {
+                self.error(
+                    Some(0),
+                    None,
+                    format!("print_direction {direction} is neither 0 nor 1"),
+                );
+            }
+        }
+        if let Some(full_layout) = header_line.full_layout {
+            if !(0..=32767).contains(&full_layout) {
+                self.error(
+                    Some(0),
+                    None,
+                    format!("full_layout {full_layout} is not between 0 and 32767"),
+                );
+            } else if old_layout_of(full_layout) != header_line.old_layout {
+                self.warning(
+                    Some(0),
+                    None,
+                    format!(
+                        "old_layout {} doesn't match full_layout {full_layout}, which implies {}",
+                        header_line.old_layout,
+                        old_layout_of(full_layout)
+                    ),
+                );
+            }
+        }
+        if let Some(count) = header_line.codetag_count {
+            if count < 0 {
+                self.error(Some(0), None, format!("codetag_count {count} is negative"));
+            }
+        }
+    }
+
+    /// check the rows of one FIGcharacter starting at line `start`
+    fn check_character(
+        &mut self,
+        lines: &[&str],
+        start: usize,
+        height: usize,
+        code: Option<u32>,
+        header_line: &HeaderLine,
+    ) {
+        let mut endmark = None;
+        let mut width = None;
+        for (i, line) in lines[start..start + height].iter().enumerate() {
+            let index = Some(start + i);
+            let is_last = i == height - 1;
+            let trimmed = line.trim_end();
+            let mut chars: Vec<char> = trimmed.chars().collect();
+            let Some(mark) = chars.pop() else {
+                self.error(index, code, "row has no endmark".to_string());
+                continue;
+            };
+
+            let row_length = chars.len() + 1;
+            self.longest_row = self.longest_row.max(row_length);
+            if header_line.max_length > 0 && row_length > header_line.max_length as usize {
+                self.error(
+                    index,
+                    code,
+                    format!(
+                        "row is {row_length} characters, longer than max_length {}",
+                        header_line.max_length
+                    ),
+                );
+            }
+
+            if mark == header_line.hardblank {
+                self.error(
+                    index,
+                    code,
+                    format!("hardblank {mark:?} is used as endmark"),
+                );
+            }
+            match endmark {
+                None => endmark = Some(mark),
+                Some(first) if first != mark => {
+                    self.error(
+                        index,
+                        code,
+                        format!("endmark {mark:?} differs from endmark {first:?} of the first row"),
+                    );
+                }
+                Some(_) => {}
+            }
+
+            if is_last && height != 1 {
+                if chars.last() == Some(&mark) {
+                    chars.pop();
+                } else {
+                    self.warning(index, code, "last row has no double endmark".to_string());
+                }
+            }
+
+            match width {
+                None => width = Some(chars.len()),
+                Some(first) if first != chars.len() => {
+                    self.error(
+                        index,
+                        code,
+                        format!(
+                            "row is {} characters wide, the first row is {first}",
+                            chars.len()
+                        ),
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// the old layout a full layout is compatible with
+fn old_layout_of(full_layout: i32) -> i32 {
+    if full_layout & 128 != 0 {
+        full_layout & 63
+    } else if full_layout & 64 != 0 {
+        0
+    } else {
+        -1
+    }
+}
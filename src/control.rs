@@ -0,0 +1,261 @@
+#[cfg(feature = "std")]
+use crate::encoding::line_of;
+use crate::error::Error;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::iter::Peekable;
+use core::str::Chars;
+#[cfg(feature = "std")]
+use std::fs;
+
+/// input encoding requested by a control file with the `b`, `u`, `h`, `j` or `g` command
+///
+/// Messages are Rust strings, so they are already decoded. The encoding is kept for callers which
+/// decode raw input themselves, only the translations are applied while rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// `b`, every byte is one character
+    Bytes,
+    /// `u`, UTF-8
+    Utf8,
+    /// `h`, HZ
+    Hz,
+    /// `j`, Shift-JIS
+    ShiftJis,
+    /// `g`, ISO 2022
+    Iso2022,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Translation {
+    first: i64,
+    last: i64,
+    offset: i64,
+}
+
+/// FIGlet control file (`.flc`), which remaps the characters of a message before they are looked
+/// up in the font
+///
+/// ```
+/// use figlet_rs::{ControlFile, FIGlet};
+///
+/// let upper = ControlFile::from_content("flc2a\nt a-z A-Z\n").unwrap();
+/// let font = FIGlet::standard().unwrap();
+/// let figure = font.render().control_file(upper).convert("rust").unwrap();
+/// assert_eq!(font.convert("RUST").unwrap().as_str(), figure.as_str());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ControlFile {
+    /// translations grouped by the `f` (freeze) command, every stage works on the output of the
+    /// stage before it
+    stages: Vec<Vec<Translation>>,
+    pub input_encoding: Option<InputEncoding>,
+}
+
+impl ControlFile {
+    /// generate ControlFile from string literal
+    pub fn from_content(contents: &str) -> Result<ControlFile, Error> {
+        let mut control_file = ControlFile {
+            stages: vec![vec![]],
+            input_encoding: None,
+        };
+
+        for (index, line) in contents.lines().enumerate() {
+            control_file
+                .read_command(line)
+                .map_err(|message| Error::Control {
+                    line: index + 1,
+                    message,
+                })?;
+        }
+
+        control_file.stages.retain(|stage| !stage.is_empty());
+        Ok(control_file)
+    }
+
+    /// generate ControlFile from specified file, which must be UTF-8 encoded
+    #[cfg(feature = "std")]
+    pub fn from_file(filename: &str) -> Result<ControlFile, Error> {
+        let bytes = fs::read(filename)?;
+        let contents = core::str::from_utf8(&bytes).map_err(|e| Error::Control {
+            line: line_of(&bytes, e.valid_up_to()),
+            message: format!("{filename} is not valid UTF-8: {e}"),
+        })?;
+        ControlFile::from_content(contents)
+    }
+
+    /// map a character code through every stage of the control file
+    pub fn translate(&self, code: u32) -> u32 {
+        let mut code = i64::from(code);
+        for stage in &self.stages {
+            if let Some(translation) = stage
+                .iter()
+                .find(|translation| translation.first <= code && code <= translation.last)
+            {
+                code += translation.offset;
+            }
+        }
+
+        u32::try_from(code).unwrap_or(0)
+    }
+
+    fn read_command(&mut self, line: &str) -> Result<(), String> {
+        let mut cursor = CommandCursor::new(line);
+        let Some(command) = cursor.peek() else {
+            return Ok(());
+        };
+
+        match command {
+            '#' => {}
+            't' => {
+                cursor.next();
+                cursor.skip_whitespace();
+                let (first, last) = cursor.read_range()?;
+                cursor.skip_whitespace();
+                let (target, _) = cursor.read_range()?;
+                self.add_translation(first, last, target);
+            }
+            '-' | '0'..='9' => {
+                let first = cursor.read_number()?;
+                cursor.skip_whitespace();
+                let target = cursor.read_number()?;
+                self.add_translation(first, first, target);
+            }
+            'f' => self.stages.push(vec![]),
+            'b' => self.input_encoding = Some(InputEncoding::Bytes),
+            'u' => self.input_encoding = Some(InputEncoding::Utf8),
+            'h' => self.input_encoding = Some(InputEncoding::Hz),
+            'j' => self.input_encoding = Some(InputEncoding::ShiftJis),
+            'g' => self.input_encoding = Some(InputEncoding::Iso2022),
+            ch if ch.is_whitespace() => {
+                cursor.skip_whitespace();
+                if cursor.peek().is_some() {
+                    return Err(format!("unexpected indentation in {line:?}"));
+                }
+            }
+            ch => return Err(format!("unknown command {ch:?}")),
+        }
+
+        Ok(())
+    }
+
+    fn add_translation(&mut self, first: i64, last: i64, target: i64) {
+        let translation = Translation {
+            first,
+            last,
+            offset: target - first,
+        };
+        self.stages
+            .last_mut()
+            .expect("control file always has a stage")
+            .push(translation);
+    }
+}
+
+/// reads characters and numbers the way figlet reads a control file line
+struct CommandCursor<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> CommandCursor<'a> {
+    fn new(line: &'a str) -> Self {
+        Self {
+            chars: line.chars().peekable(),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        self.chars.next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    /// a number with optional sign, `0x` for hexadecimal and a leading `0` for octal
+    fn read_number(&mut self) -> Result<i64, String> {
+        let sign = if self.peek() == Some('-') {
+            self.next();
+            -1
+        } else {
+            1
+        };
+
+        let mut radix = 10;
+        if self.peek() == Some('0') {
+            self.next();
+            radix = 8;
+            if matches!(self.peek(), Some('x' | 'X')) {
+                self.next();
+                radix = 16;
+            }
+        }
+
+        let mut value: i64 = 0;
+        let mut digits = 0;
+        while let Some(digit) = self.peek().and_then(|ch| ch.to_digit(radix)) {
+            self.next();
+            digits += 1;
+            value = value
+                .checked_mul(i64::from(radix))
+                .and_then(|value| value.checked_add(i64::from(digit)))
+                .ok_or_else(|| "number is too large".to_string())?;
+        }
+
+        if digits == 0 && radix != 8 {
+            return Err("expected a number".to_string());
+        }
+        Ok(sign * value)
+    }
+
+    /// a literal character, or a backslash escape like `\n`, `\\` or `\0x41`. Like figlet, any other
+    /// escaped character, e.g. `\x`, stands for itself
+    fn read_character(&mut self) -> Result<i64, String> {
+        let ch = self
+            .next()
+            .ok_or_else(|| "expected a character".to_string())?;
+        if ch != '\\' {
+            return Ok(i64::from(u32::from(ch)));
+        }
+
+        let escaped = self
+            .peek()
+            .ok_or_else(|| "expected a character after '\\'".to_string())?;
+        let code = match escaped {
+            'a' => 7,
+            'b' => 8,
+            'e' => 27,
+            'f' => 12,
+            'n' => 10,
+            'r' => 13,
+            't' => 9,
+            'v' => 11,
+            '-' | '0'..='9' => return self.read_number(),
+            ch => i64::from(u32::from(ch)),
+        };
+        self.next();
+        Ok(code)
+    }
+
+    /// a single character or a range like `a-z`
+    fn read_range(&mut self) -> Result<(i64, i64), String> {
+        let first = self.read_character()?;
+        if self.peek() == Some('-') {
+            self.next();
+            let last = self.read_character()?;
+            if last < first {
+                return Err(format!("range {first}-{last} is reversed"));
+            }
+            Ok((first, last))
+        } else {
+            Ok((first, first))
+        }
+    }
+}
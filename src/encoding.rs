@@ -0,0 +1,71 @@
+use crate::error::Error;
+use alloc::string::String;
+
+/// the upper half of code page 437, the lower half is the same as ASCII
+const CP437_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»\
+░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀\
+αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{A0}";
+
+/// text encoding of a font file
+///
+/// TOIlet fonts are always UTF-8. FIGlet fonts from the figlet font database are often Latin-1,
+/// and some DOS fonts use code page 437.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    /// ISO-8859-1, every byte is the code point of the same value
+    Latin1,
+    /// IBM PC code page 437
+    Cp437,
+}
+
+impl Encoding {
+    /// UTF-8 when the bytes are valid UTF-8, otherwise Latin-1, which can decode any bytes
+    pub fn detect(bytes: &[u8]) -> Encoding {
+        if core::str::from_utf8(bytes).is_ok() {
+            Encoding::Utf8
+        } else {
+            Encoding::Latin1
+        }
+    }
+
+    /// decode the bytes, only UTF-8 can fail
+    pub fn decode(self, bytes: &[u8]) -> Result<String, Error> {
+        match self {
+            Encoding::Utf8 => match core::str::from_utf8(bytes) {
+                Ok(contents) => Ok(String::from(contents)),
+                Err(error) => Err(Error::Encoding {
+                    line: line_of(bytes, error.valid_up_to()),
+                    error,
+                }),
+            },
+            Encoding::Latin1 => Ok(bytes.iter().map(|byte| char::from(*byte)).collect()),
+            Encoding::Cp437 => Ok(bytes
+                .iter()
+                .map(|byte| match byte {
+                    0..=0x7F => char::from(*byte),
+                    _ => CP437_HIGH
+                        .chars()
+                        .nth(usize::from(byte - 0x80))
+                        .expect("code page 437 table has 128 characters"),
+                })
+                .collect()),
+        }
+    }
+}
+
+/// decode with the declared encoding, or detect it when there is none
+pub(crate) fn decode_bytes(bytes: &[u8], encoding: Option<Encoding>) -> Result<String, Error> {
+    encoding
+        .unwrap_or_else(|| Encoding::detect(bytes))
+        .decode(bytes)
+}
+
+/// the line of the byte at `offset`, starting at 1
+pub(crate) fn line_of(bytes: &[u8], offset: usize) -> usize {
+    bytes[..offset]
+        .iter()
+        .filter(|byte| **byte == b'\n')
+        .count()
+        + 1
+}
@@ -0,0 +1,118 @@
+use crate::check::Diagnostic;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::Utf8Error;
+#[cfg(feature = "std")]
+use std::error;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use zip::result::ZipError;
+
+/// error which can occur while loading a FIGlet or Toilet font, or a control file
+///
+/// Line numbers start at 1 and count every line of the font file, including the header line.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// the font file can't be read
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// the zip archive of a packaged font is broken or empty
+    #[cfg(feature = "std")]
+    Zip(ZipError),
+    /// the font is not valid UTF-8, `line` is the line of the first invalid byte
+    Encoding { line: usize, error: Utf8Error },
+    /// the header line is malformed or doesn't match the rest of the font
+    Header(String),
+    /// the font starts with neither the FIGlet signature `flf2a` nor the Toilet signature
+    /// `tlf2a`, the beginning of the first line is kept
+    UnknownSignature(String),
+    /// the FIGcharacter with the given code can't be read
+    Glyph {
+        line: usize,
+        code: u32,
+        message: String,
+    },
+    /// a FIGcharacter added with `FontBuilder` doesn't fit the font
+    InvalidGlyph { code: u32, message: String },
+    /// a codetag line or the codetag section can't be read
+    Codetag { line: usize, message: String },
+    /// a line of a control file can't be read
+    Control { line: usize, message: String },
+    /// strict parsing found problems in the font, every error `check_font` reports
+    Invalid(Vec<Diagnostic>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(e) => write!(f, "can't read font: {e}"),
+            #[cfg(feature = "std")]
+            Error::Zip(e) => write!(f, "can't unpack zip font: {e}"),
+            Error::Encoding { line, error } => {
+                write!(f, "line {line}: font is not valid UTF-8: {error}")
+            }
+            Error::Header(message) => write!(f, "illegal header line: {message}"),
+            Error::UnknownSignature(signature) => write!(
+                f,
+                "unknown font signature {signature:?}, expected flf2a for FIGlet or tlf2a for Toilet fonts"
+            ),
+            Error::Glyph {
+                line,
+                code,
+                message,
+            } => write!(
+                f,
+                "line {line}: can't read FIGcharacter {code} (U+{code:04X}): {message}"
+            ),
+            Error::InvalidGlyph { code, message } => {
+                write!(f, "illegal FIGcharacter {code} (U+{code:04X}): {message}")
+            }
+            Error::Codetag { line, message } => {
+                write!(f, "line {line}: illegal codetag: {message}")
+            }
+            Error::Control { line, message } => {
+                write!(f, "line {line}: illegal control file command: {message}")
+            }
+            Error::Invalid(diagnostics) => {
+                write!(f, "font breaks the FIGfont spec")?;
+                if let Some(first) = diagnostics.first() {
+                    write!(f, ": {first}")?;
+                }
+                if diagnostics.len() > 1 {
+                    write!(f, " (and {} more errors)", diagnostics.len() - 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Zip(e) => Some(e),
+            Error::Encoding { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ZipError> for Error {
+    fn from(e: ZipError) -> Self {
+        Error::Zip(e)
+    }
+}
@@ -1,52 +1,153 @@
+use crate::builtin::BuiltinFont;
+use crate::check::Diagnostic;
+use crate::encoding::Encoding;
+use crate::error::Error;
+use crate::metadata::FontMetadata;
+#[cfg(feature = "std")]
+use crate::shared::load_font_file;
+#[cfg(feature = "serde")]
+use crate::shared::UncheckedFont;
 use crate::shared::{
-    load_font_file, parse_font_content, render, FIGcharacter, FIGure, FontData, HeaderLine,
+    character_by_name, character_name, parse_font_bytes, parse_font_bytes_with_mode,
+    parse_font_content, parse_font_content_with_mode, render, FIGcharacter, FIGure, FontData,
+    HeaderLine, ParseMode, RenderBuilder, RenderError, RenderOptions,
 };
-use std::collections::HashMap;
+use crate::writer::write_font_content;
+#[cfg(feature = "std")]
+use crate::writer::{write_font_file, write_font_zip};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 /// FIGlet font, which will hold the mapping from u32 code to FIGcharacter
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedFont"))]
 pub struct FIGlet {
     pub header_line: HeaderLine,
     pub comments: String,
-    pub fonts: HashMap<u32, FIGcharacter>,
+    pub fonts: BTreeMap<u32, FIGcharacter>,
 }
 
 impl FIGlet {
     /// generate FIGlet font from string literal
-    pub fn from_content(contents: &str) -> Result<FIGlet, String> {
+    pub fn from_content(contents: &str) -> Result<FIGlet, Error> {
         Ok(parse_font_content(contents)?.into())
     }
 
-    /// generate FIGlet font from specified file
-    pub fn from_file(fontname: &str) -> Result<FIGlet, String> {
-        Ok(load_font_file(fontname)?.into())
+    /// generate FIGlet font from string literal in the given mode, along with the warnings
+    pub fn from_content_with_mode(
+        contents: &str,
+        mode: ParseMode,
+    ) -> Result<(FIGlet, Vec<Diagnostic>), Error> {
+        let (data, warnings) = parse_font_content_with_mode(contents, mode)?;
+        Ok((data.into(), warnings))
+    }
+
+    /// generate FIGlet font from specified file, which may be UTF-8 or Latin-1 encoded
+    #[cfg(feature = "std")]
+    pub fn from_file(fontname: &str) -> Result<FIGlet, Error> {
+        Ok(load_font_file(fontname, None)?.into())
+    }
+
+    /// generate FIGlet font from specified file with a known encoding
+    #[cfg(feature = "std")]
+    pub fn from_file_with_encoding(fontname: &str, encoding: Encoding) -> Result<FIGlet, Error> {
+        Ok(load_font_file(fontname, Some(encoding))?.into())
+    }
+
+    /// generate FIGlet font from the bytes of a plain or zip-packaged font, which may be UTF-8
+    /// or Latin-1 encoded
+    pub fn from_bytes(bytes: &[u8]) -> Result<FIGlet, Error> {
+        Ok(parse_font_bytes(bytes, None)?.into())
+    }
+
+    /// generate FIGlet font from bytes in the given mode, along with the warnings
+    pub fn from_bytes_with_mode(
+        bytes: &[u8],
+        mode: ParseMode,
+    ) -> Result<(FIGlet, Vec<Diagnostic>), Error> {
+        let (data, warnings) = parse_font_bytes_with_mode(bytes, None, mode)?;
+        Ok((data.into(), warnings))
+    }
+
+    /// generate FIGlet font from bytes with a known encoding
+    pub fn from_bytes_with_encoding(bytes: &[u8], encoding: Encoding) -> Result<FIGlet, Error> {
+        Ok(parse_font_bytes(bytes, Some(encoding))?.into())
     }
 
     /// the standard FIGlet font, which you can find [`fontdb`]
     ///
     /// [`fontdb`]: http://www.figlet.org/fontdb.cgi
-    pub fn standard() -> Result<FIGlet, String> {
-        Ok(parse_font_content(include_str!("../resources/standard.flf"))?.into())
+    pub fn standard() -> Result<FIGlet, Error> {
+        FIGlet::from_bytes(BuiltinFont::Standard.bytes())
     }
 
     /// the small FIGlet font bundled with the crate
-    pub fn small() -> Result<FIGlet, String> {
-        Ok(parse_font_content(include_str!("../resources/small.flf"))?.into())
+    pub fn small() -> Result<FIGlet, Error> {
+        FIGlet::from_bytes(BuiltinFont::Small.bytes())
     }
 
     /// the big FIGlet font bundled with the crate
-    pub fn big() -> Result<FIGlet, String> {
-        Ok(parse_font_content(include_str!("../resources/big.flf"))?.into())
+    pub fn big() -> Result<FIGlet, Error> {
+        FIGlet::from_bytes(BuiltinFont::Big.bytes())
     }
 
     /// the slant FIGlet font bundled with the crate
-    pub fn slant() -> Result<FIGlet, String> {
-        Ok(parse_font_content(include_str!("../resources/slant.flf"))?.into())
+    pub fn slant() -> Result<FIGlet, Error> {
+        FIGlet::from_bytes(BuiltinFont::Slant.bytes())
+    }
+
+    /// credits, license and layout of the font, see `FontMetadata`
+    pub fn metadata(&self) -> FontMetadata {
+        FontMetadata::new(&self.header_line, &self.comments)
+    }
+
+    /// the FIGcharacter with the given codetag name, e.g. `LATIN CAPITAL LETTER A WITH DIAERESIS`
+    pub fn character_by_name(&self, name: &str) -> Option<&FIGcharacter> {
+        character_by_name(&self.fonts, name)
+    }
+
+    /// the codetag name of the FIGcharacter for `code`
+    pub fn character_name(&self, code: u32) -> Option<&str> {
+        character_name(&self.fonts, code)
+    }
+
+    /// serialize the font to the contents of a `.flf` file
+    pub fn to_content(&self) -> String {
+        write_font_content(&self.header_line, &self.comments, &self.fonts)
+    }
+
+    /// serialize the font to a zip archive holding the single file `file_name`
+    #[cfg(feature = "std")]
+    pub fn to_zip_bytes(&self, file_name: &str) -> Result<Vec<u8>, Error> {
+        write_font_zip(&self.to_content(), file_name)
+    }
+
+    /// write the font to specified file, zip-packaged when the name ends with `.zip`
+    #[cfg(feature = "std")]
+    pub fn write_file(&self, fontname: &str) -> Result<(), Error> {
+        write_font_file(&self.to_content(), fontname)
     }
 
-    /// convert string literal to FIGure
+    /// convert string literal to FIGure, every `\n` or `\r\n` starts a new row of text
     pub fn convert(&self, message: &str) -> Option<FIGure<'_>> {
-        render(&self.header_line, &self.fonts, message)
+        self.convert_with_options(message, &RenderOptions::default())
+            .ok()
+    }
+
+    /// start rendering with non-default options, see `RenderBuilder`
+    pub fn render(&self) -> RenderBuilder<'_> {
+        RenderBuilder::new(&self.header_line, &self.fonts)
+    }
+
+    /// convert string literal to FIGure with the given RenderOptions
+    pub fn convert_with_options(
+        &self,
+        message: &str,
+        options: &RenderOptions,
+    ) -> Result<FIGure<'_>, RenderError> {
+        render(&self.header_line, &self.fonts, message, options)
     }
 }
 
@@ -59,3 +160,13 @@ impl From<FontData> for FIGlet {
         }
     }
 }
+
+/// a deserialized font is checked before it can be rendered
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedFont> for FIGlet {
+    type Error = Error;
+
+    fn try_from(font: UncheckedFont) -> Result<Self, Self::Error> {
+        Ok(FontData::try_from(font)?.into())
+    }
+}
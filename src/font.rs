@@ -0,0 +1,195 @@
+use crate::error::Error;
+use crate::figlet::FIGlet;
+use crate::metadata::FontMetadata;
+use crate::shared::{
+    unpack_font_bytes, FIGcharacter, FIGure, FontKind, HeaderLine, RenderBuilder, RenderError,
+    RenderOptions,
+};
+use crate::toilet::Toilet;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// either a FIGlet or a Toilet font, for code which handles whatever font the user picks
+///
+/// `Font::open` and `Font::from_bytes` tell the kind from the font itself, so the caller doesn't
+/// need to know it in advance.
+///
+/// ```
+/// use figlet_rs::{BuiltinFont, Font, FontKind};
+///
+/// for builtin in [BuiltinFont::Standard, BuiltinFont::Future] {
+///     let font: Font = builtin.load().unwrap();
+///     assert_eq!(builtin.kind(), font.kind());
+///     assert!(font.convert("Test").is_some());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Font {
+    FIGlet(FIGlet),
+    Toilet(Toilet),
+}
+
+impl Font {
+    /// load a font file of either kind, see `from_bytes`
+    #[cfg(feature = "std")]
+    pub fn open(path: impl AsRef<Path>) -> Result<Font, Error> {
+        Font::from_bytes(&fs::read(path)?)
+    }
+
+    /// generate a font from the bytes of a plain or zip-packaged font of either kind
+    ///
+    /// The signature of the header line decides between FIGlet and Toilet, and fails with
+    /// `Error::UnknownSignature` for anything else. FIGlet fonts may then be UTF-8 or Latin-1
+    /// encoded, Toilet fonts must be UTF-8.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Font, Error> {
+        let bytes = unpack_font_bytes(bytes)?;
+        Font::from_bytes_of_kind(&bytes, FontKind::detect(&bytes)?)
+    }
+
+    /// generate a font of either kind from string literal, see `from_bytes`
+    pub fn from_content(contents: &str) -> Result<Font, Error> {
+        Ok(match FontKind::detect(contents.as_bytes())? {
+            FontKind::FIGlet => Font::FIGlet(FIGlet::from_content(contents)?),
+            FontKind::Toilet => Font::Toilet(Toilet::from_content(contents)?),
+        })
+    }
+
+    /// parse a plain or zip-packaged font of a known kind
+    pub(crate) fn from_bytes_of_kind(bytes: &[u8], kind: FontKind) -> Result<Font, Error> {
+        Ok(match kind {
+            FontKind::FIGlet => Font::FIGlet(FIGlet::from_bytes(bytes)?),
+            FontKind::Toilet => Font::Toilet(Toilet::from_bytes(bytes)?),
+        })
+    }
+
+    pub fn kind(&self) -> FontKind {
+        match self {
+            Font::FIGlet(_) => FontKind::FIGlet,
+            Font::Toilet(_) => FontKind::Toilet,
+        }
+    }
+
+    pub fn header_line(&self) -> &HeaderLine {
+        match self {
+            Font::FIGlet(font) => &font.header_line,
+            Font::Toilet(font) => &font.header_line,
+        }
+    }
+
+    pub fn comments(&self) -> &str {
+        match self {
+            Font::FIGlet(font) => &font.comments,
+            Font::Toilet(font) => &font.comments,
+        }
+    }
+
+    /// every FIGcharacter of the font by code
+    pub fn fonts(&self) -> &BTreeMap<u32, FIGcharacter> {
+        match self {
+            Font::FIGlet(font) => &font.fonts,
+            Font::Toilet(font) => &font.fonts,
+        }
+    }
+
+    /// the FIGcharacter for `code`
+    pub fn get(&self, code: u32) -> Option<&FIGcharacter> {
+        self.fonts().get(&code)
+    }
+
+    /// the FIGcharacter with the given codetag name, see `FIGlet::character_by_name`
+    pub fn character_by_name(&self, name: &str) -> Option<&FIGcharacter> {
+        match self {
+            Font::FIGlet(font) => font.character_by_name(name),
+            Font::Toilet(font) => font.character_by_name(name),
+        }
+    }
+
+    /// the codetag name of the FIGcharacter for `code`
+    pub fn character_name(&self, code: u32) -> Option<&str> {
+        match self {
+            Font::FIGlet(font) => font.character_name(code),
+            Font::Toilet(font) => font.character_name(code),
+        }
+    }
+
+    /// credits, license and layout of the font, see `FontMetadata`
+    pub fn metadata(&self) -> FontMetadata {
+        match self {
+            Font::FIGlet(font) => font.metadata(),
+            Font::Toilet(font) => font.metadata(),
+        }
+    }
+
+    /// serialize the font to the contents of a `.flf` or `.tlf` file
+    pub fn to_content(&self) -> String {
+        match self {
+            Font::FIGlet(font) => font.to_content(),
+            Font::Toilet(font) => font.to_content(),
+        }
+    }
+
+    /// serialize the font to a zip archive holding the single file `file_name`
+    #[cfg(feature = "std")]
+    pub fn to_zip_bytes(&self, file_name: &str) -> Result<Vec<u8>, Error> {
+        match self {
+            Font::FIGlet(font) => font.to_zip_bytes(file_name),
+            Font::Toilet(font) => font.to_zip_bytes(file_name),
+        }
+    }
+
+    /// write the font to specified file, zip-packaged when the name ends with `.zip`
+    #[cfg(feature = "std")]
+    pub fn write_file(&self, fontname: &str) -> Result<(), Error> {
+        match self {
+            Font::FIGlet(font) => font.write_file(fontname),
+            Font::Toilet(font) => font.write_file(fontname),
+        }
+    }
+
+    /// convert string literal to FIGure, every `\n` or `\r\n` starts a new row of text
+    pub fn convert(&self, message: &str) -> Option<FIGure<'_>> {
+        match self {
+            Font::FIGlet(font) => font.convert(message),
+            Font::Toilet(font) => font.convert(message),
+        }
+    }
+
+    /// start rendering with non-default options, see `RenderBuilder`
+    pub fn render(&self) -> RenderBuilder<'_> {
+        match self {
+            Font::FIGlet(font) => font.render(),
+            Font::Toilet(font) => font.render(),
+        }
+    }
+
+    /// convert string literal to FIGure with the given RenderOptions
+    pub fn convert_with_options(
+        &self,
+        message: &str,
+        options: &RenderOptions,
+    ) -> Result<FIGure<'_>, RenderError> {
+        match self {
+            Font::FIGlet(font) => font.convert_with_options(message, options),
+            Font::Toilet(font) => font.convert_with_options(message, options),
+        }
+    }
+}
+
+impl From<FIGlet> for Font {
+    fn from(font: FIGlet) -> Self {
+        Font::FIGlet(font)
+    }
+}
+
+impl From<Toilet> for Font {
+    fn from(font: Toilet) -> Self {
+        Font::Toilet(font)
+    }
+}
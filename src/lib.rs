@@ -21,24 +21,71 @@
 //! [`figlet`]: http://www.figlet.org
 //! [`figfont`]: http://www.jave.de/figlet/figfont.html
 //! [`fontdb`]: http://www.figlet.org/fontdb.cgi
+//!
+//! # `no_std`
+//!
+//! Without the default `std` feature the crate only needs `alloc`. Fonts are then parsed with
+//! `from_content` or `from_bytes`, e.g. from `include_str!`, and rendered as usual. Loading files,
+//! zip-packaged fonts, the font resolver and writing files need `std`.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
 
+mod builder;
+mod builtin;
+mod check;
+mod control;
+mod encoding;
+mod error;
 mod figlet;
+mod font;
+mod metadata;
+#[cfg(feature = "std")]
+mod resolver;
 mod shared;
+mod stack;
 mod toilet;
+mod writer;
 
+pub use builder::FontBuilder;
+pub use builtin::BuiltinFont;
+#[cfg(feature = "std")]
+pub use check::check_font_file;
+pub use check::{check_font, check_font_bytes, Diagnostic, Severity};
+pub use control::{ControlFile, InputEncoding};
+pub use encoding::Encoding;
+pub use error::Error;
 pub use figlet::FIGlet;
-pub use shared::{FIGcharacter, FIGure, HeaderLine};
+pub use font::Font;
+pub use metadata::FontMetadata;
+#[cfg(feature = "std")]
+pub use resolver::{FontResolver, ResolvedFont};
+pub use shared::{
+    FIGcharacter, FIGure, FontKind, HeaderLine, Justification, Layout, MissingCharacter, ParseMode,
+    PrintDirection, RenderBuilder, RenderError, RenderOptions, SmushRules,
+};
+pub use stack::FontStack;
 pub use toilet::Toilet;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     use crate::shared::{
-        FontData, SM_BIGX, SM_EQUAL, SM_HARDBLANK, SM_HIERARCHY, SM_KERN, SM_LOWLINE, SM_PAIR,
-        SM_SMUSH,
+        vertical_smush_chars, FontData, SM_BIGX, SM_EQUAL, SM_HARDBLANK, SM_HIERARCHY,
+        SM_HORIZONTAL_LINE, SM_KERN, SM_LOWLINE, SM_PAIR, SM_SMUSH, SM_VERTICAL_EQUAL,
+        SM_VERTICAL_HIERARCHY, SM_VERTICAL_KERN, SM_VERTICAL_LINE, SM_VERTICAL_LOWLINE,
+        SM_VERTICAL_SMUSH,
     };
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
     use std::fs;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+    use std::{format, vec};
 
     fn fixture(path: &str) -> String {
         let root = Path::new(env!("CARGO_MANIFEST_DIR"));
@@ -63,6 +110,15 @@ mod tests {
         assert_eq!(fixture(fixture_path), figure.as_str());
     }
 
+    fn assert_rows_fit(figure: &FIGure, width: usize) {
+        for row in figure.as_str().lines() {
+            assert!(
+                row.chars().count() <= width,
+                "{row:?} is wider than {width}"
+            );
+        }
+    }
+
     #[test]
     fn test_new_headerline() {
         let line = "flf2a$ 6 5 20 15 3 0 143 229";
@@ -144,6 +200,510 @@ mod tests {
         assert_eq!(5, figure.characters.len());
     }
 
+    #[test]
+    fn test_convert_multi_line() {
+        let mut font = FIGlet::standard().unwrap();
+        font.header_line.full_layout = Some(font.header_line.effective_layout() & 255);
+        let hello = font.convert("Hello").unwrap().as_str();
+        let rust = font.convert("Rust").unwrap().as_str();
+
+        let figure = font.convert("Hello\nRust").unwrap();
+        assert_eq!(9, figure.characters.len());
+        assert_eq!(format!("{hello}{rust}"), figure.as_str());
+        assert_eq!(12, figure.as_str().lines().count());
+
+        let crlf = font.convert("Hello\r\nRust").unwrap();
+        assert_eq!(figure.as_str(), crlf.as_str());
+    }
+
+    #[test]
+    fn test_convert_multi_line_vertical_smushing() {
+        let font = FIGlet::standard().unwrap();
+        let figure = font.convert("Hello\nRust").unwrap();
+        let output = figure.as_str();
+        let rows: Vec<&str> = output.lines().collect();
+
+        assert_eq!(10, rows.len());
+        assert_eq!("|_|_|_|\\___|_|_|\\___/ ", rows[4]);
+        assert_eq!("|  _ \\ _   _ ___| |_  ", rows[5]);
+    }
+
+    #[test]
+    fn test_convert_multi_line_vertical_fitting() {
+        let mut font = FIGlet::standard().unwrap();
+        font.header_line.full_layout =
+            Some((font.header_line.effective_layout() & 255) | SM_VERTICAL_KERN);
+        let figure = font.convert("Hello\nRust").unwrap();
+        let output = figure.as_str();
+        let rows: Vec<&str> = output.lines().collect();
+
+        assert_eq!(11, rows.len());
+        assert_eq!("|_| |_|\\___|_|_|\\___/ ", rows[4]);
+        assert_eq!(" ____            _    ", rows[5]);
+    }
+
+    #[test]
+    fn test_vertical_smush_rules() {
+        let smush =
+            |rule, upper, lower| vertical_smush_chars(rule | SM_VERTICAL_SMUSH, upper, lower);
+        assert_eq!(Some('#'), smush(SM_VERTICAL_EQUAL, '#', '#'));
+        assert_eq!(Some('/'), smush(SM_VERTICAL_LOWLINE, '_', '/'));
+        assert_eq!(Some('{'), smush(SM_VERTICAL_HIERARCHY, '{', '|'));
+        assert_eq!(Some('='), smush(SM_HORIZONTAL_LINE, '-', '_'));
+        assert_eq!(Some('='), smush(SM_HORIZONTAL_LINE, '_', '-'));
+        assert_eq!(Some('|'), smush(SM_VERTICAL_LINE, '|', '|'));
+        assert_eq!(None, smush(SM_VERTICAL_EQUAL, '-', '_'));
+        assert_eq!(Some('b'), vertical_smush_chars(SM_VERTICAL_SMUSH, 'a', 'b'));
+        assert_eq!(None, vertical_smush_chars(0, 'a', 'b'));
+    }
+
+    #[test]
+    fn test_convert_multi_line_keeps_blank_lines() {
+        let font = FIGlet::standard().unwrap();
+        let figure = font.convert("A\n\nB").unwrap();
+        let output = figure.as_str();
+        let rows: Vec<&str> = output.lines().collect();
+
+        assert_eq!(18, rows.len());
+        assert!(rows[6..12].iter().all(|row| row.is_empty()));
+    }
+
+    #[test]
+    fn test_toilet_convert_multi_line() {
+        let font = Toilet::smblock().unwrap();
+        let test = font.convert("Test").unwrap().as_str();
+        let figure = font.convert("Test\nTest").unwrap();
+        assert_eq!(format!("{test}{test}"), figure.as_str());
+    }
+
+    #[test]
+    fn test_convert_with_width_wraps_at_word_boundary() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            width: Some(40),
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("Hello Rust", &options).unwrap();
+        assert_rows_fit(&figure, 40);
+        assert_eq!(
+            font.convert("Hello\nRust").unwrap().as_str(),
+            figure.as_str()
+        );
+    }
+
+    #[test]
+    fn test_convert_with_large_width_matches_unwrapped() {
+        let font = FIGlet::standard().unwrap();
+        let message = "Hello wrapping world ".repeat(60);
+
+        let figure = font.render().width(100_000).convert(&message).unwrap();
+        assert_eq!(font.convert(&message).unwrap().as_str(), figure.as_str());
+
+        let wrapped = font.render().width(80).convert(&message).unwrap();
+        assert_rows_fit(&wrapped, 80);
+    }
+
+    #[test]
+    fn test_convert_with_width_breaks_long_word() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            width: Some(20),
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("Hello", &options).unwrap();
+        assert_rows_fit(&figure, 20);
+        assert_eq!(font.convert("Hell\no").unwrap().as_str(), figure.as_str());
+    }
+
+    #[test]
+    fn test_convert_with_wide_enough_width_is_unchanged() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            width: Some(80),
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(
+            font.convert("Hello Rust").unwrap().as_str(),
+            font.convert_with_options("Hello Rust", &options)
+                .unwrap()
+                .as_str()
+        );
+    }
+
+    #[test]
+    fn test_justification_inside_width() {
+        let font = FIGlet::standard().unwrap();
+        let plain = font.convert("Hi").unwrap().as_str();
+        let plain_width = plain.lines().map(|row| row.chars().count()).max().unwrap();
+
+        for (justification, padding) in [
+            (Justification::Left, 0),
+            (Justification::Auto, 0),
+            (Justification::Center, (40 - plain_width) / 2),
+            (Justification::Right, 40 - plain_width),
+        ] {
+            let options = RenderOptions {
+                width: Some(40),
+                justification,
+                ..RenderOptions::default()
+            };
+            let figure = font.convert_with_options("Hi", &options).unwrap();
+            let expected: String = plain
+                .lines()
+                .map(|row| format!("{}{row}\n", " ".repeat(padding)))
+                .collect();
+            assert_eq!(expected, figure.as_str(), "{justification:?}");
+        }
+    }
+
+    #[test]
+    fn test_justification_without_width_uses_widest_row() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            justification: Justification::Right,
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("Hello\nRust", &options).unwrap();
+        let widths: Vec<usize> = figure
+            .as_str()
+            .lines()
+            .filter(|row| !row.trim().is_empty())
+            .map(|row| row.chars().count())
+            .collect();
+        assert!(widths.iter().all(|width| *width == widths[0]));
+    }
+
+    #[test]
+    fn test_justification_auto_follows_print_direction() {
+        let mut font = FIGlet::standard().unwrap();
+        font.header_line.print_direction = Some(1);
+        let options = RenderOptions {
+            width: Some(40),
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("Hi", &options).unwrap();
+        for row in figure.as_str().lines() {
+            assert_eq!(40, row.chars().count());
+        }
+    }
+
+    #[test]
+    fn test_justification_keeps_hardblanks_as_spaces() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            width: Some(40),
+            justification: Justification::Center,
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("a b", &options).unwrap();
+        assert!(!figure.as_str().contains(font.header_line.hardblank));
+    }
+
+    #[test]
+    fn test_paragraph_mode_joins_soft_line_breaks() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            paragraph: true,
+            ..RenderOptions::default()
+        };
+
+        let figure = font
+            .convert_with_options("Hi\nthere\r\nRust\nis\n", &options)
+            .unwrap();
+        assert_eq!(
+            font.convert("Hi there Rust is").unwrap().as_str(),
+            figure.as_str()
+        );
+        assert_eq!(
+            Err(RenderError::Empty),
+            font.convert_with_options("\n\n", &options)
+                .map(|figure| figure.as_str())
+        );
+    }
+
+    #[test]
+    fn test_paragraph_mode_keeps_every_blank_line() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            paragraph: true,
+            ..RenderOptions::default()
+        };
+
+        // like figlet, the newline right before "Rust" still becomes a space
+        let figure = font
+            .convert_with_options("Hi\nthere\n\n\n\nRust\nis", &options)
+            .unwrap();
+        assert_eq!(
+            font.convert("Hi there\n\n\n Rust is").unwrap().as_str(),
+            figure.as_str()
+        );
+    }
+
+    #[test]
+    fn test_paragraph_mode_breaks_before_indented_lines() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            paragraph: true,
+            ..RenderOptions::default()
+        };
+
+        let figure = font
+            .convert_with_options("Hi\nthere\n  Rust\n\tis\nfun", &options)
+            .unwrap();
+        assert_eq!(
+            font.convert("Hi there\n  Rust\n\tis fun").unwrap().as_str(),
+            figure.as_str()
+        );
+    }
+
+    #[test]
+    fn test_paragraph_mode_with_width() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            width: Some(40),
+            paragraph: true,
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("Hello\nRust", &options).unwrap();
+        assert_rows_fit(&figure, 40);
+        assert_eq!(
+            font.convert_with_options("Hello Rust", &options)
+                .unwrap()
+                .as_str(),
+            figure.as_str()
+        );
+    }
+
+    #[test]
+    fn test_render_builder_matches_options() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            width: Some(40),
+            justification: Justification::Center,
+            layout: Layout::Fitting,
+            direction: PrintDirection::LeftToRight,
+            missing_character: MissingCharacter::Placeholder,
+            paragraph: true,
+            control_files: vec![],
+        };
+
+        let builder = font
+            .render()
+            .width(40)
+            .center()
+            .layout(Layout::Fitting)
+            .direction(PrintDirection::LeftToRight)
+            .missing_character(MissingCharacter::Placeholder)
+            .paragraph(true);
+        assert_eq!(
+            font.convert_with_options("Hello\nRust 世", &options)
+                .unwrap()
+                .as_str(),
+            builder.convert("Hello\nRust 世").unwrap().as_str()
+        );
+        assert_eq!(
+            Justification::Right,
+            builder.right().render_options().justification
+        );
+    }
+
+    #[test]
+    fn test_render_builder_defaults_match_convert() {
+        let figlet = FIGlet::slant().unwrap();
+        assert_eq!(
+            figlet.convert("Test").unwrap().as_str(),
+            figlet.render().convert("Test").unwrap().as_str()
+        );
+
+        let toilet = Toilet::future().unwrap();
+        let figure = toilet
+            .render()
+            .width(20)
+            .right()
+            .convert("Test Test")
+            .unwrap();
+        assert_rows_fit(&figure, 20);
+        assert_eq!(
+            Err(RenderError::MissingCharacters(vec!['世'])),
+            toilet
+                .render()
+                .missing_character(MissingCharacter::Error)
+                .convert("世")
+                .map(|figure| figure.as_str())
+        );
+    }
+
+    #[test]
+    fn test_control_file_translations() {
+        let control = ControlFile::from_content(
+            "flc2a\n# comment\n\nt a-c x-z\nt \\0x64 D\n101 69\nt \\  _\nt \\x y\n",
+        )
+        .unwrap();
+
+        assert_eq!('y' as u32, control.translate('b' as u32));
+        assert_eq!('D' as u32, control.translate('d' as u32));
+        assert_eq!('E' as u32, control.translate('e' as u32));
+        assert_eq!('_' as u32, control.translate(' ' as u32));
+        assert_eq!('f' as u32, control.translate('f' as u32));
+        // an escaped letter without a special meaning stands for itself
+        assert_eq!('y' as u32, control.translate('x' as u32));
+        assert_eq!(None, control.input_encoding);
+    }
+
+    #[test]
+    fn test_control_file_freeze_stages() {
+        let single_stage = ControlFile::from_content("t a b\nt b c\n").unwrap();
+        assert_eq!('b' as u32, single_stage.translate('a' as u32));
+
+        let two_stages = ControlFile::from_content("t a b\nf\nt b c\n").unwrap();
+        assert_eq!('c' as u32, two_stages.translate('a' as u32));
+        assert_eq!('c' as u32, two_stages.translate('b' as u32));
+    }
+
+    #[test]
+    fn test_control_file_input_encoding() {
+        let control = ControlFile::from_content("flc2a\nu\n").unwrap();
+        assert_eq!(Some(InputEncoding::Utf8), control.input_encoding);
+
+        let control = ControlFile::from_content("b\n0xC1 0x0430\n").unwrap();
+        assert_eq!(Some(InputEncoding::Bytes), control.input_encoding);
+        assert_eq!(0x0430, control.translate(0xC1));
+    }
+
+    #[test]
+    fn test_control_file_invalid() {
+        match ControlFile::from_content("flc2a\nt a-z A-Z\nq what\n").unwrap_err() {
+            Error::Control { line, .. } => assert_eq!(3, line),
+            other => panic!("unexpected error {other:?}"),
+        }
+        assert!(ControlFile::from_content("t z-a a\n").is_err());
+        assert!(ControlFile::from_content("t a\n").is_err());
+    }
+
+    #[test]
+    fn test_control_file_not_utf8() {
+        let dir = temp_font_dir("control");
+        let path = dir.join("latin1.flc");
+        fs::write(&path, b"flc2a\nt a-z A-Z\nt \xC4 A\n").unwrap();
+        let filename = path.to_str().unwrap();
+
+        let error = ControlFile::from_file(filename).unwrap_err();
+        assert!(error.to_string().contains(filename));
+        match error {
+            Error::Control { line, message } => {
+                assert_eq!(3, line);
+                assert!(message.contains("is not valid UTF-8"));
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_with_control_files() {
+        let font = FIGlet::standard().unwrap();
+        let upper = ControlFile::from_content("t a-z A-Z\n").unwrap();
+        let swap = ControlFile::from_content("t A B\n").unwrap();
+
+        let figure = font
+            .render()
+            .control_file(upper)
+            .control_file(swap)
+            .convert("abc")
+            .unwrap();
+        assert_eq!(font.convert("BBC").unwrap().as_str(), figure.as_str());
+    }
+
+    #[test]
+    fn test_missing_character_skip() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions::default();
+
+        assert_eq!(
+            font.convert("Hello").unwrap().as_str(),
+            font.convert_with_options("Hello世界", &options)
+                .unwrap()
+                .as_str()
+        );
+        assert_eq!(
+            Err(RenderError::Empty),
+            font.convert_with_options("世界", &options)
+                .map(|figure| figure.as_str())
+        );
+    }
+
+    #[test]
+    fn test_empty_message_renders_nothing() {
+        // nothing is rendered for a message without FIGcharacters, however high the font is
+        let font = FIGlet::from_content("flf2a$ 100000000 1 2 0 0\n").unwrap();
+        assert_eq!(
+            Err(RenderError::Empty),
+            font.convert_with_options("x\ny", &RenderOptions::default())
+                .map(|figure| figure.as_str())
+        );
+    }
+
+    #[test]
+    fn test_missing_character_code_zero() {
+        let mut font = FIGlet::standard().unwrap();
+        let mut missing = font.fonts[&('?' as u32)].clone();
+        missing.code = 0;
+        font.fonts.insert(0, missing);
+        let options = RenderOptions {
+            missing_character: MissingCharacter::CodeZero,
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("Hi世", &options).unwrap();
+        assert_eq!(3, figure.characters.len());
+        assert_eq!(0, figure.characters[2].code);
+        assert_eq!(font.convert("Hi?").unwrap().as_str(), figure.as_str());
+    }
+
+    #[test]
+    fn test_missing_character_placeholder() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            missing_character: MissingCharacter::Placeholder,
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("A世", &options).unwrap();
+        assert_eq!(2, figure.characters.len());
+        assert_eq!('世' as u32, figure.characters[1].code);
+        assert_eq!(6, figure.characters[1].height);
+        assert!(matches!(figure.characters[1], Cow::Owned(_)));
+        assert!(figure.as_str().lines().next().unwrap().ends_with("+-+"));
+
+        let toilet = Toilet::wideterm().unwrap();
+        let figure = toilet.convert_with_options("世", &options).unwrap();
+        assert_eq!("[]\n", figure.as_str());
+    }
+
+    #[test]
+    fn test_missing_character_error() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            missing_character: MissingCharacter::Error,
+            ..RenderOptions::default()
+        };
+
+        let error = font
+            .convert_with_options("Hello世界!世", &options)
+            .unwrap_err();
+        assert_eq!(RenderError::MissingCharacters(vec!['世', '界']), error);
+        assert_eq!("font can't render characters: '世' '界'", error.to_string());
+        assert!(font.convert_with_options("Hello!", &options).is_ok());
+    }
+
     #[test]
     fn test_figure_as_str() {
         let font = FIGlet::standard().unwrap();
@@ -219,7 +779,13 @@ mod tests {
     #[test]
     fn test_smush_rule_equal() {
         let font = full_smush_font();
-        let renderer = crate::shared::render(&font.header_line, &font.fonts, "||").unwrap();
+        let renderer = crate::shared::render(
+            &font.header_line,
+            &font.fonts,
+            "||",
+            &RenderOptions::default(),
+        )
+        .unwrap();
         assert!(renderer.is_not_empty());
     }
 
@@ -230,6 +796,114 @@ mod tests {
         assert!(figure.is_not_empty());
     }
 
+    #[test]
+    fn test_layout_override_does_not_touch_font() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            layout: Layout::ControlledSmushing(SmushRules::ALL),
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("_/|>", &options).unwrap();
+        assert_eq!(
+            full_smush_font().convert("_/|>").unwrap().as_str(),
+            figure.as_str()
+        );
+        assert_eq!(Some(24463), font.header_line.full_layout);
+    }
+
+    #[test]
+    fn test_layout_full_width_and_fitting() {
+        let font = FIGlet::standard().unwrap();
+        let full_width = RenderOptions {
+            layout: Layout::FullWidth,
+            ..RenderOptions::default()
+        };
+        let fitting = RenderOptions {
+            layout: Layout::Fitting,
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("FIGlet", &full_width).unwrap();
+        let glyphs: Vec<String> = "FIGlet"
+            .chars()
+            .map(|ch| font.fonts[&(ch as u32)].characters[1].replace('$', " "))
+            .collect();
+        assert_eq!(glyphs.concat(), figure.as_str().lines().nth(1).unwrap());
+
+        let width = |figure: FIGure| figure.as_str().lines().next().unwrap().chars().count();
+        let fitted = width(font.convert_with_options("FIGlet", &fitting).unwrap());
+        let smushed = width(font.convert("FIGlet").unwrap());
+        assert!(fitted < glyphs.concat().chars().count());
+        assert!(smushed < fitted);
+    }
+
+    #[test]
+    fn test_layout_universal_smushing() {
+        let font = FIGlet::standard().unwrap();
+        let options = RenderOptions {
+            layout: Layout::UniversalSmushing,
+            ..RenderOptions::default()
+        };
+
+        let universal = font.convert_with_options("FIGlet", &options).unwrap();
+        let default = font.convert("FIGlet").unwrap();
+        assert!(universal.is_not_empty());
+        assert_ne!(default.as_str(), universal.as_str());
+    }
+
+    #[test]
+    fn test_print_direction_override() {
+        let font = FIGlet::standard().unwrap();
+        let right_to_left = RenderOptions {
+            direction: PrintDirection::RightToLeft,
+            ..RenderOptions::default()
+        };
+
+        // like figlet, only the first FIGcharacter loses its leading blank column, which is the
+        // leftmost one for left-to-right and the rightmost one for right-to-left
+        let figure = font.convert_with_options("tsuR", &right_to_left).unwrap();
+        let expected: String = font
+            .convert("Rust")
+            .unwrap()
+            .as_str()
+            .lines()
+            .map(|row| format!(" {row}\n"))
+            .collect();
+        assert_eq!(expected, figure.as_str());
+        assert_eq!(Some(0), font.header_line.print_direction);
+    }
+
+    #[test]
+    fn test_print_direction_override_of_right_to_left_font() {
+        let mut font = FIGlet::standard().unwrap();
+        font.header_line.print_direction = Some(1);
+        let left_to_right = RenderOptions {
+            width: Some(40),
+            direction: PrintDirection::LeftToRight,
+            ..RenderOptions::default()
+        };
+
+        let figure = font.convert_with_options("Rust", &left_to_right).unwrap();
+        assert_eq!(
+            FIGlet::standard()
+                .unwrap()
+                .convert("Rust")
+                .unwrap()
+                .as_str(),
+            figure.as_str()
+        );
+    }
+
+    #[test]
+    fn test_smush_rules_bits() {
+        let rules = SmushRules::EQUAL | SmushRules::BIGX;
+        assert_eq!(SM_EQUAL | SM_BIGX, rules.bits());
+        assert!(rules.contains(SmushRules::EQUAL));
+        assert!(!rules.contains(SmushRules::PAIR));
+        assert!(SmushRules::ALL.contains(rules));
+    }
+
     #[test]
     fn test_figure_is_not_empty() {
         let font = FIGlet::standard().unwrap();
@@ -351,23 +1025,278 @@ mod tests {
     }
 
     #[test]
-    fn test_toilet_header_supports_tlf_signature() {
-        let header = HeaderLine::try_from("tlf2a$ 4 3 8 0 16 0 64 0").unwrap();
-        assert_eq!("tlf2a", header.signature);
-        assert_eq!(4, header.height);
+    fn test_error_header() {
+        let error = HeaderLine::try_from("flf2a$ 6 five 16 15 11").unwrap_err();
+        assert!(matches!(error, Error::Header(_)));
+        assert_eq!(
+            "illegal header line: can't parse required field:baseline of five to i32",
+            error.to_string()
+        );
+        assert!(matches!(FIGlet::from_content(""), Err(Error::Header(_))));
+        assert!(matches!(
+            FIGlet::from_content("flf2a$ 1 1 2 -1 5\n"),
+            Err(Error::Header(_))
+        ));
     }
 
     #[test]
-    fn test_toilet_from_content() {
-        let content = fixture("resources/smblock.tlf");
-        let font = Toilet::from_content(&content).unwrap();
-        assert_eq!("tlf2a", font.header_line.signature);
-        assert!(font.convert("Test").is_some());
+    fn test_error_io_keeps_source() {
+        use std::error::Error as _;
+
+        let error = FIGlet::from_file("resources/missing.flf").unwrap_err();
+        assert!(matches!(&error, Error::Io(e) if e.kind() == std::io::ErrorKind::NotFound));
+        assert!(error.source().is_some());
     }
 
     #[test]
-    fn test_toilet_builtin_text_font_loading() {
-        let font1 = Toilet::smblock().unwrap();
+    fn test_error_encoding() {
+        let error = Toilet::from_bytes(b"tlf2a$ 1 1 2 -1 0\n\xff@\n").unwrap_err();
+        assert!(matches!(error, Error::Encoding { line: 2, .. }));
+        // the error doesn't keep a copy of the font
+        let bytes = [b"tlf2a$ 1 1 2 -1 0\n".as_slice(), &[0xff; 4096]].concat();
+        let error = Toilet::from_bytes(&bytes).unwrap_err();
+        assert!(format!("{error:?}").len() < 100);
+    }
+
+    #[test]
+    fn test_latin1_font_is_detected() {
+        let mut bytes = fixture("resources/small.flf").into_bytes();
+        let comment = b"Includes ISO Latin-1";
+        let start = bytes
+            .windows(comment.len())
+            .position(|window| window == comment)
+            .unwrap();
+        bytes[start..start + comment.len()].copy_from_slice(b"\xC4\xD6 ISO Latin-1 fonts");
+
+        let font = FIGlet::from_bytes(&bytes).unwrap();
+        assert!(font.comments.contains("ÄÖ ISO Latin-1 fonts"));
+        assert_eq!(
+            FIGlet::small().unwrap().convert("Test").unwrap().as_str(),
+            font.convert("Test").unwrap().as_str()
+        );
+
+        let declared = FIGlet::from_bytes_with_encoding(&bytes, Encoding::Latin1).unwrap();
+        assert_eq!(font.comments, declared.comments);
+        assert!(matches!(
+            FIGlet::from_bytes_with_encoding(&bytes, Encoding::Utf8),
+            Err(Error::Encoding { .. })
+        ));
+    }
+
+    #[test]
+    fn test_latin1_glyph_art() {
+        let font = FIGlet::from_bytes(b"flf2a$ 1 1 3 -1 0\n @\n\xA4\xA4@\n").unwrap();
+        assert_eq!("¤¤\n", font.convert("!").unwrap().as_str());
+    }
+
+    #[test]
+    fn test_cp437_font() {
+        let bytes = b"flf2a$ 1 1 3 -1 0\n @\n\xB0\xDB@\n";
+        let font = FIGlet::from_bytes_with_encoding(bytes, Encoding::Cp437).unwrap();
+        assert_eq!("░█\n", font.convert("!").unwrap().as_str());
+        assert_eq!(Encoding::Latin1, Encoding::detect(bytes));
+        assert_eq!(Encoding::Utf8, Encoding::detect("█".as_bytes()));
+    }
+
+    #[test]
+    fn test_toilet_font_must_be_utf8() {
+        let bytes = b"tlf2a$ 1 1 3 -1 0\n @\n\xA4\xA4@\n";
+        assert!(matches!(
+            Toilet::from_bytes(bytes),
+            Err(Error::Encoding { .. })
+        ));
+        assert!(Toilet::from_bytes("tlf2a$ 1 1 3 -1 0\n @\n▄▄@\n".as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_error_zip() {
+        let error = FIGlet::from_bytes(b"PK\x03\x04broken").unwrap_err();
+        assert!(matches!(error, Error::Zip(_)));
+    }
+
+    #[test]
+    fn test_error_glyph_has_line_and_code() {
+        let error = FIGlet::from_content("flf2a$ 2 1 2 -1 0\n @\n@@\n!@\n\n").unwrap_err();
+        match &error {
+            Error::Glyph { line, code, .. } => {
+                assert_eq!(5, *line);
+                assert_eq!('!' as u32, *code);
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+        assert!(error
+            .to_string()
+            .starts_with("line 5: can't read FIGcharacter 33 (U+0021)"));
+    }
+
+    #[test]
+    fn test_error_codetag_has_line() {
+        let standard = fixture("resources/standard.flf");
+        let lines = standard.lines().count();
+
+        let bad_code = format!("{standard}0xZZ  BROKEN\n{}", "x@\n".repeat(5) + "x@@\n");
+        match FIGlet::from_content(&bad_code).unwrap_err() {
+            Error::Codetag { line, .. } => assert_eq!(lines + 1, line),
+            other => panic!("unexpected error {other:?}"),
+        }
+
+        let stray_line = format!("{standard}stray@\n");
+        match FIGlet::from_content(&stray_line).unwrap_err() {
+            Error::Codetag { line, .. } => assert_eq!(lines + 1, line),
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_lenient_parsing_multibyte_hardblank() {
+        let small = fixture("resources/small.flf");
+        let contents = small.replacen("flf2a$", "flf2a█", 1).replace("$@", "█@");
+        for mode in [ParseMode::Lenient, ParseMode::Strict] {
+            let (font, warnings) = FIGlet::from_content_with_mode(&contents, mode).unwrap();
+            assert!(warnings.is_empty());
+            assert_eq!('█', font.header_line.hardblank);
+            assert_eq!(
+                FIGlet::small().unwrap().convert("Test").unwrap().as_str(),
+                font.convert("Test").unwrap().as_str()
+            );
+        }
+
+        let broken = small.replacen("flf2a$", "fl█", 1);
+        assert!(FIGlet::from_content_with_mode(&broken, ParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_lenient_parsing_skips_broken_codetags() {
+        let small = fixture("resources/small.flf");
+        let font = FIGlet::small().unwrap();
+        let broken = small.replacen("160  NO-BREAK SPACE", "0xZZ  BROKEN", 1) + "stray@\n";
+        assert!(FIGlet::from_content(&broken).is_err());
+
+        let (lenient, warnings) =
+            FIGlet::from_content_with_mode(&broken, ParseMode::Lenient).unwrap();
+        assert_eq!(font.fonts.len() - 1, lenient.fonts.len());
+        assert!(!lenient.fonts.contains_key(&160));
+        assert_eq!(font.fonts[&161].characters, lenient.fonts[&161].characters);
+
+        let lines: Vec<Option<usize>> = warnings.iter().map(|warning| warning.line).collect();
+        assert_eq!(vec![Some(522), Some(small.lines().count() + 1)], lines);
+        assert!(warnings
+            .iter()
+            .all(|warning| warning.severity == Severity::Warning));
+
+        let (_, warnings) = FIGlet::from_content_with_mode(&small, ParseMode::Lenient).unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_lenient_parsing_skips_broken_glyph() {
+        let small = fixture("resources/small.flf");
+        let mut lines: Vec<&str> = small.lines().collect();
+        lines[16] = "";
+
+        let contents = lines.join("\n");
+        match FIGlet::from_content(&contents).unwrap_err() {
+            Error::Glyph { line, code, .. } => assert_eq!((17, 33), (line, code)),
+            other => panic!("unexpected error {other:?}"),
+        }
+
+        let (font, warnings) =
+            FIGlet::from_content_with_mode(&contents, ParseMode::Lenient).unwrap();
+        assert!(!font.fonts.contains_key(&33));
+        assert_eq!(1, warnings.len());
+        assert_eq!((Some(17), Some(33)), (warnings[0].line, warnings[0].code));
+    }
+
+    #[test]
+    fn test_header_rejects_height_below_one() {
+        for height in ["0", "-1"] {
+            let contents = fixture("resources/small.flf").replacen(
+                "flf2a$ 5 4",
+                &format!("flf2a$ {height} 4"),
+                1,
+            );
+            for mode in [ParseMode::Strict, ParseMode::Lenient] {
+                assert!(FIGlet::from_content_with_mode(&contents, mode).is_err());
+            }
+            match FIGlet::from_content(&contents).unwrap_err() {
+                Error::Header(message) => {
+                    assert_eq!(format!("height {height} is less than 1"), message)
+                }
+                other => panic!("unexpected error {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_header_rejects_negative_max_length() {
+        let contents = fixture("resources/small.flf").replacen("flf2a$ 5 4 13", "flf2a$ 5 4 -1", 1);
+        match FIGlet::from_content(&contents).unwrap_err() {
+            Error::Header(message) => assert_eq!("max_length -1 is negative", message),
+            other => panic!("unexpected error {other:?}"),
+        }
+        assert!(FIGlet::from_content_with_mode(&contents, ParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_strict_parsing_rejects_spec_errors() {
+        let (font, warnings) =
+            FIGlet::from_content_with_mode(&fixture("resources/standard.flf"), ParseMode::Strict)
+                .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(FIGlet::standard().unwrap().fonts.len(), font.fonts.len());
+
+        let (_, warnings) = Toilet::from_bytes_with_mode(
+            include_bytes!("../resources/mono9.tlf"),
+            ParseMode::Strict,
+        )
+        .unwrap();
+        assert_eq!(1, warnings.len());
+
+        // the default parser accepts a baseline below the FIGcharacters, strict mode doesn't
+        let contents = fixture("resources/small.flf").replacen("flf2a$ 5 4", "flf2a$ 5 6", 1);
+        assert!(FIGlet::from_content(&contents).is_ok());
+        match FIGlet::from_content_with_mode(&contents, ParseMode::Strict).unwrap_err() {
+            Error::Invalid(errors) => {
+                assert_eq!(1, errors.len());
+                assert_eq!(Some(1), errors[0].line);
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_strict_parsing_checks_before_parsing() {
+        let small = fixture("resources/small.flf");
+        let lines: Vec<&str> = small.lines().collect();
+        let truncated = lines[..40].join("\n");
+        let zero_height = small.replacen("flf2a$ 5 4", "flf2a$ 0 4", 1);
+
+        for contents in [truncated, zero_height] {
+            match FIGlet::from_content_with_mode(&contents, ParseMode::Strict).unwrap_err() {
+                Error::Invalid(errors) => assert!(!errors.is_empty()),
+                other => panic!("unexpected error {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_toilet_header_supports_tlf_signature() {
+        let header = HeaderLine::try_from("tlf2a$ 4 3 8 0 16 0 64 0").unwrap();
+        assert_eq!("tlf2a", header.signature);
+        assert_eq!(4, header.height);
+    }
+
+    #[test]
+    fn test_toilet_from_content() {
+        let content = fixture("resources/smblock.tlf");
+        let font = Toilet::from_content(&content).unwrap();
+        assert_eq!("tlf2a", font.header_line.signature);
+        assert!(font.convert("Test").is_some());
+    }
+
+    #[test]
+    fn test_toilet_builtin_text_font_loading() {
+        let font1 = Toilet::smblock().unwrap();
         let font2 = Toilet::from_file("resources/smblock.tlf").unwrap();
         assert_eq!(font1.header_line.header_line, font2.header_line.header_line);
         assert_eq!(font1.comments, font2.comments);
@@ -443,6 +1372,780 @@ mod tests {
         assert_eq!(6, figure.characters.len());
     }
 
+    fn temp_font_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("figlet-rs-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_resolver_finds_fonts_by_name() {
+        let resolver = FontResolver::new().dir("resources");
+
+        let standard = resolver.resolve("standard").unwrap();
+        assert_eq!(Path::new("resources/standard.flf"), standard.path);
+        assert_eq!(FontKind::FIGlet, standard.kind);
+        assert_eq!("standard", standard.name);
+
+        let mono12 = resolver.resolve("mono12").unwrap();
+        assert_eq!(Path::new("resources/mono12.tlf"), mono12.path);
+        assert_eq!(FontKind::Toilet, mono12.kind);
+
+        assert_eq!(standard, resolver.resolve("standard.flf").unwrap());
+        assert_eq!(
+            standard,
+            FontResolver::new().resolve("resources/standard").unwrap()
+        );
+        assert!(resolver.resolve("banner3").is_none());
+        assert!(resolver.resolve("standard.tlf").is_none());
+
+        let font = mono12.load().unwrap();
+        assert_eq!(FontKind::Toilet, font.kind());
+        assert_eq!(
+            Toilet::mono12().unwrap().convert("Test").unwrap().as_str(),
+            font.convert("Test").unwrap().as_str()
+        );
+    }
+
+    #[test]
+    fn test_resolver_search_order_and_zipped_fonts() {
+        let dir = temp_font_dir("resolver");
+        fs::copy("resources/small.flf", dir.join("standard.flf")).unwrap();
+        fs::copy("resources/mono9.tlf", dir.join("packed.tlf.zip")).unwrap();
+
+        let resolver = FontResolver::new().dir(&dir).dir("resources");
+        assert_eq!(
+            dir.join("standard.flf"),
+            resolver.resolve("standard").unwrap().path
+        );
+
+        let packed = resolver.resolve("packed").unwrap();
+        assert_eq!(FontKind::Toilet, packed.kind);
+        let font = Toilet::from_file(packed.path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            Toilet::mono9().unwrap().convert("Test").unwrap().as_str(),
+            font.convert("Test").unwrap().as_str()
+        );
+
+        let names: Vec<String> = FontResolver::new()
+            .dir(&dir)
+            .list()
+            .into_iter()
+            .map(|font| font.name)
+            .collect();
+        assert_eq!(vec!["packed", "standard"], names);
+
+        let listed = resolver.list();
+        for name in ["packed", "standard", "small", "mono9"] {
+            assert!(listed.iter().any(|font| font.name == name), "{name}");
+        }
+        let standards: Vec<&ResolvedFont> = listed
+            .iter()
+            .filter(|font| font.name == "standard")
+            .collect();
+        assert_eq!(1, standards.len());
+        assert_eq!(dir.join("standard.flf"), standards[0].path);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolver_system_dirs() {
+        let resolver = FontResolver::new()
+            .dir("resources")
+            .system_dirs_with(Some(PathBuf::from("fonts")));
+        assert_eq!(
+            vec![
+                PathBuf::from("resources"),
+                PathBuf::from("fonts"),
+                PathBuf::from("/usr/share/figlet"),
+                PathBuf::from("/usr/local/share/figlet"),
+                PathBuf::from("/usr/share/figlet/fonts"),
+                PathBuf::from("/usr/local/share/figlet/fonts"),
+            ],
+            resolver.dirs()
+        );
+
+        let resolver = FontResolver::new().system_dirs_with(None);
+        assert_eq!(Path::new("/usr/share/figlet"), resolver.dirs()[0]);
+    }
+
+    fn assert_same_glyphs(
+        expected: &BTreeMap<u32, FIGcharacter>,
+        actual: &BTreeMap<u32, FIGcharacter>,
+    ) {
+        assert_eq!(expected.len(), actual.len());
+        for (code, glyph) in expected {
+            let reloaded = &actual[code];
+            assert_eq!(glyph.code, reloaded.code);
+            assert_eq!(glyph.characters, reloaded.characters, "FIGcharacter {code}");
+            assert_eq!(glyph.width, reloaded.width);
+            assert_eq!(glyph.height, reloaded.height);
+            assert_eq!(glyph.name, reloaded.name, "FIGcharacter {code}");
+        }
+    }
+
+    #[test]
+    fn test_write_reload_builtin_fonts() {
+        for font in [
+            FIGlet::standard().unwrap(),
+            FIGlet::small().unwrap(),
+            FIGlet::big().unwrap(),
+            FIGlet::slant().unwrap(),
+        ] {
+            let reloaded = FIGlet::from_content(&font.to_content()).unwrap();
+            assert_same_glyphs(&font.fonts, &reloaded.fonts);
+            assert_eq!(font.comments, reloaded.comments);
+            assert_eq!(font.header_line.hardblank, reloaded.header_line.hardblank);
+            assert_eq!(
+                font.header_line.effective_layout(),
+                reloaded.header_line.effective_layout()
+            );
+        }
+
+        for font in [
+            Toilet::smblock().unwrap(),
+            Toilet::mono12().unwrap(),
+            Toilet::future().unwrap(),
+            Toilet::wideterm().unwrap(),
+            Toilet::mono9().unwrap(),
+        ] {
+            let reloaded = Toilet::from_content(&font.to_content()).unwrap();
+            assert_same_glyphs(&font.fonts, &reloaded.fonts);
+            assert_eq!(font.comments, reloaded.comments);
+        }
+    }
+
+    #[test]
+    fn test_codetag_names() {
+        let standard = FIGlet::standard().unwrap();
+        assert_eq!(Some("NO-BREAK SPACE"), standard.character_name(160));
+        assert_eq!(None, standard.character_name('A' as u32));
+        assert_eq!(None, standard.character_name(0x2588));
+        assert_eq!(
+            196,
+            standard
+                .character_by_name("latin capital letter a with diaeresis")
+                .unwrap()
+                .code
+        );
+        assert!(standard.character_by_name("SNOWMAN").is_none());
+        assert!(standard.to_content().contains("\n160  NO-BREAK SPACE\n"));
+
+        let smblock = Toilet::smblock().unwrap();
+        assert_eq!(
+            Some("À LATIN CAPITAL LETTER A WITH GRAVE"),
+            smblock.character_name(0xC0)
+        );
+        for name in [
+            "LATIN CAPITAL LETTER A WITH GRAVE",
+            "À LATIN CAPITAL LETTER A WITH GRAVE",
+        ] {
+            assert_eq!(0xC0, smblock.character_by_name(name).unwrap().code);
+        }
+        assert!(smblock.character_by_name("À").is_none());
+
+        let mut builder = FontBuilder::from_figlet(&standard);
+        builder.insert(160, "\n\n\n\n\n").unwrap();
+        assert_eq!(
+            Some("NO-BREAK SPACE"),
+            builder.get(160).unwrap().name.as_deref()
+        );
+    }
+
+    #[test]
+    fn test_write_header_line_is_regenerated() {
+        let mut font = FIGlet::standard().unwrap();
+        font.comments = "first\nsecond".to_string();
+        font.fonts.retain(|code, _| *code < 127 || *code == 0x2500);
+
+        let contents = font.to_content();
+        let header = contents.lines().next().unwrap();
+        assert_eq!("flf2a$ 6 5 16 15 2 0 24463", header);
+
+        let reloaded = FIGlet::from_content(&contents).unwrap();
+        assert_eq!(2, reloaded.header_line.comment_lines);
+        assert_eq!(None, reloaded.header_line.codetag_count);
+        assert_eq!(95, reloaded.fonts.len());
+    }
+
+    #[test]
+    fn test_write_codetags_and_endmarks() {
+        let mut font = FIGlet::small().unwrap();
+        font.fonts.remove(&196);
+        font.fonts.insert(
+            0x2588,
+            FIGcharacter {
+                code: 0x2588,
+                characters: vec![
+                    "@@ ".to_string(),
+                    " @".to_string(),
+                    "#".to_string(),
+                    String::new(),
+                    "x ".to_string(),
+                ],
+                width: 3,
+                height: 5,
+                name: None,
+            },
+        );
+
+        let contents = font.to_content();
+        // `$` is the hardblank of small, so it is skipped as endmark
+        assert!(contents.contains("\n9608\n@@ %\n @%\n#%\n%\nx %%\n"));
+        assert!(!check_font(&contents)
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("endmark")));
+        // the missing Deutsch character is written as an empty FIGcharacter to keep the order
+        let reloaded = FIGlet::from_content(&contents).unwrap();
+        assert_eq!(0, reloaded.fonts[&196].width);
+        // the six named Deutsch characters left are written again as codetags to keep the names
+        let codetag_count = font.fonts.len() - 101 + 6;
+        assert_eq!(
+            Some(codetag_count as i32),
+            reloaded.header_line.codetag_count
+        );
+        assert!(contents.contains("\n214  LATIN CAPITAL LETTER O WITH DIAERESIS\n"));
+        assert_eq!(
+            Some("LATIN CAPITAL LETTER O WITH DIAERESIS"),
+            reloaded.character_name(214)
+        );
+        assert_eq!(
+            font.fonts[&0x2588].characters,
+            reloaded.fonts[&0x2588].characters
+        );
+        assert_eq!(font.fonts[&214].characters, reloaded.fonts[&214].characters);
+    }
+
+    #[test]
+    fn test_check_builtin_fonts() {
+        for name in ["standard", "small", "big", "slant"] {
+            let diagnostics = check_font_file(&format!("resources/{name}.flf")).unwrap();
+            assert_eq!(Vec::<Diagnostic>::new(), diagnostics, "{name}");
+        }
+
+        // the toilet fonts declare codetag_count 0, which is only worth a warning
+        let diagnostics = check_font_bytes(include_bytes!("../resources/mono9.tlf")).unwrap();
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Severity::Warning, diagnostics[0].severity);
+    }
+
+    #[test]
+    fn test_check_reports_every_problem() {
+        let mut lines: Vec<String> = fixture("resources/small.flf")
+            .lines()
+            .map(String::from)
+            .collect();
+        lines[0] = "flf2a$ 5 9 13 15 10 0 22415 7".to_string();
+        lines[16] = "  _  @".to_string();
+        lines[22] = " ( | )#".to_string();
+        lines[27] = " xxxxxxxxxxxxxxxxxx@".to_string();
+        lines[31] = "  _ $".to_string();
+
+        let diagnostics = check_font(&lines.join("\n"));
+        let find = |line: usize, message: &str| {
+            diagnostics
+                .iter()
+                .find(|d| d.line == Some(line) && d.message.contains(message))
+                .unwrap_or_else(|| panic!("no {message:?} on line {line} in {diagnostics:#?}"))
+        };
+
+        assert_eq!(Severity::Error, find(1, "baseline 9").severity);
+        assert_eq!(Severity::Error, find(1, "codetag_count is 7").severity);
+        assert_eq!(Some(33), find(18, "the first row is 5").code);
+        assert_eq!(Some(34), find(23, "endmark '#' differs").code);
+        assert_eq!(Some(35), find(28, "longer than max_length 13").code);
+        assert_eq!(Some(36), find(32, "hardblank '$' is used as endmark").code);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Error));
+
+        let message = find(23, "endmark").to_string();
+        assert_eq!(
+            "line 23: error: FIGcharacter 34 (U+0022): endmark '#' differs from endmark '@' of the first row",
+            message
+        );
+    }
+
+    #[test]
+    fn test_check_multibyte_hardblank() {
+        let header_line = HeaderLine::try_from("tlf2a█ 1 1 3 -1 0").unwrap();
+        assert_eq!(
+            ("tlf2a", '█'),
+            (header_line.signature.as_str(), header_line.hardblank)
+        );
+
+        let diagnostics = check_font("tlf2a█ 1 1 3 -1 0\n");
+        assert!(
+            diagnostics
+                .iter()
+                .all(|d| d.message == "missing required FIGcharacter"
+                    || d.message.contains("Deutsch"))
+        );
+
+        let diagnostics = check_font("fl█ 1 1 3 -1 0\n");
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Severity::Error, diagnostics[0].severity);
+    }
+
+    #[test]
+    fn test_check_missing_deutsch_characters() {
+        let contents = fixture("resources/standard.flf");
+        let lines: Vec<&str> = contents.lines().collect();
+        let header_line = HeaderLine::try_from(lines[0]).unwrap();
+        let end = 1 + header_line.comment_lines as usize + 100 * header_line.height as usize;
+
+        let diagnostics = check_font(&lines[..end].join("\n"));
+        let missing: Vec<u32> = diagnostics.iter().filter_map(|d| d.code).collect();
+        assert_eq!(vec![252, 223], missing);
+        assert!(diagnostics[0].message.contains("Deutsch"));
+    }
+
+    #[test]
+    fn test_write_zip_and_file() {
+        let font = Toilet::future().unwrap();
+        let bytes = font.to_zip_bytes("future.tlf").unwrap();
+        assert!(bytes.starts_with(b"PK\x03\x04"));
+        assert_same_glyphs(&font.fonts, &Toilet::from_bytes(&bytes).unwrap().fonts);
+
+        let dir = temp_font_dir("writer");
+        let zipped = dir.join("future.tlf.zip");
+        font.write_file(zipped.to_str().unwrap()).unwrap();
+        assert_same_glyphs(
+            &font.fonts,
+            &Toilet::from_file(zipped.to_str().unwrap()).unwrap().fonts,
+        );
+
+        let plain = dir.join("standard.flf");
+        let standard = FIGlet::standard().unwrap();
+        standard.write_file(plain.to_str().unwrap()).unwrap();
+        assert_eq!(standard.to_content(), fs::read_to_string(&plain).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_builtin_fonts_load_by_name() {
+        for builtin in BuiltinFont::ALL {
+            assert_eq!(Some(*builtin), BuiltinFont::from_name(builtin.name()));
+            let glyphs = match builtin.kind() {
+                FontKind::FIGlet => FIGlet::from_bytes(builtin.bytes()).unwrap().fonts,
+                FontKind::Toilet => Toilet::from_bytes(builtin.bytes()).unwrap().fonts,
+            };
+            assert!(glyphs.len() >= 95, "{}", builtin.name());
+        }
+
+        assert_eq!(None, BuiltinFont::from_name("banner"));
+        assert_eq!(
+            FIGlet::standard().unwrap().to_content(),
+            FIGlet::from_bytes(BuiltinFont::Standard.bytes())
+                .unwrap()
+                .to_content()
+        );
+    }
+
+    #[test]
+    fn test_figure_into_owned() {
+        let figure = {
+            let font = FIGlet::standard().unwrap();
+            let figure = font.convert("Test").unwrap();
+            let expected = figure.as_str();
+            let owned = figure.into_owned();
+            assert_eq!(expected, owned.as_str());
+            owned
+        };
+        assert!(figure
+            .characters
+            .iter()
+            .all(|character| matches!(character, Cow::Owned(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_font_roundtrip() {
+        let font = FIGlet::small().unwrap();
+        let json = serde_json::to_string(&font).unwrap();
+        let restored: FIGlet = serde_json::from_str(&json).unwrap();
+        assert_same_glyphs(&font.fonts, &restored.fonts);
+        assert_eq!(
+            font.header_line.header_line,
+            restored.header_line.header_line
+        );
+        assert_eq!(font.comments, restored.comments);
+        assert_eq!(
+            font.convert("Serde").unwrap().as_str(),
+            restored.convert("Serde").unwrap().as_str()
+        );
+
+        let toilet = Toilet::future().unwrap();
+        let restored: Toilet =
+            serde_json::from_str(&serde_json::to_string(&toilet).unwrap()).unwrap();
+        assert_same_glyphs(&toilet.fonts, &restored.fonts);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_broken_fonts() {
+        let json = serde_json::to_value(FIGlet::small().unwrap()).unwrap();
+        let broken = |change: fn(&mut serde_json::Value)| {
+            let mut json = json.clone();
+            change(&mut json);
+            serde_json::from_value::<FIGlet>(json)
+                .unwrap_err()
+                .to_string()
+        };
+
+        let message = broken(|json| {
+            json["fonts"]["65"]["characters"]
+                .as_array_mut()
+                .unwrap()
+                .pop();
+        });
+        assert!(message.contains("FIGcharacter has 4 rows"), "{message}");
+        let message = broken(|json| json["fonts"]["65"]["width"] = 40.into());
+        assert!(message.contains("FIGcharacter has width 40"), "{message}");
+        let message = broken(|json| json["fonts"]["65"]["code"] = 66.into());
+        assert!(message.contains("is stored as 65"), "{message}");
+        let message = broken(|json| json["header_line"]["height"] = 0.into());
+        assert!(message.contains("height 0 is less than 1"), "{message}");
+
+        let mut json = serde_json::to_value(Font::from(Toilet::future().unwrap())).unwrap();
+        json["Toilet"]["fonts"]["65"]["characters"] = serde_json::json!([]);
+        assert!(serde_json::from_value::<Font>(json).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_figure_roundtrip() {
+        let font = FIGlet::standard().unwrap();
+        let figure = font.convert("Hi\nthere").unwrap();
+        let json = serde_json::to_string(&figure).unwrap();
+
+        let restored: FIGure<'static> = serde_json::from_str(&json).unwrap();
+        assert_eq!(figure.as_str(), restored.as_str());
+        assert_eq!(figure.height, restored.height);
+        assert_eq!(figure.characters.len(), restored.characters.len());
+    }
+
+    #[test]
+    fn test_figlet_metadata() {
+        let metadata = FIGlet::standard().unwrap().metadata();
+        assert_eq!(Some("Standard"), metadata.name.as_deref());
+        assert_eq!(
+            Some("Glenn Chappell & Ian Chai"),
+            metadata.author.as_deref()
+        );
+        assert_eq!(Some("3/93"), metadata.date.as_deref());
+        assert_eq!(Some("2.1"), metadata.figlet_release.as_deref());
+        assert_eq!(
+            Some("Permission is hereby given to modify this font, as long as the modifier's name is placed on a comment line."),
+            metadata.license.as_deref()
+        );
+        assert_eq!(
+            vec!["ISO Latin-1", "Latin-{2,3,4,5}"],
+            metadata.character_sets
+        );
+        assert_eq!(2, metadata.modifications.len());
+        assert!(metadata.modifications[0].starts_with("Modified for figlet 2.2 by John Cowan"));
+        assert_eq!(
+            vec!["cowan@ccil.org", "solution@earthlink.net"],
+            metadata.emails
+        );
+
+        assert_eq!(
+            (6, 5, 16),
+            (metadata.height, metadata.baseline, metadata.max_length)
+        );
+        assert_eq!(
+            Layout::ControlledSmushing(
+                SmushRules::EQUAL | SmushRules::LOWLINE | SmushRules::HIERARCHY | SmushRules::PAIR
+            ),
+            metadata.layout
+        );
+        assert_eq!(PrintDirection::LeftToRight, metadata.direction);
+
+        let big = FIGlet::big().unwrap().metadata();
+        assert_eq!(Some("2.2"), big.figlet_release.as_deref());
+        assert_eq!(vec!["ISO Latin-1", "Greek"], big.character_sets);
+    }
+
+    #[test]
+    fn test_toilet_metadata() {
+        let metadata = Toilet::future().unwrap().metadata();
+        assert_eq!(Some("Future"), metadata.name.as_deref());
+        assert_eq!(Some("Sam Hocevar"), metadata.author.as_deref());
+        assert_eq!(Some("2006/10/01"), metadata.date.as_deref());
+        assert_eq!(None, metadata.figlet_release);
+        assert!(metadata
+            .license
+            .unwrap()
+            .starts_with("This font is free software. It comes without any warranty"));
+        assert_eq!(vec!["sam@hocevar.net"], metadata.emails);
+        // `Missing characters: # < > ^` doesn't name a character set
+        assert!(metadata.character_sets.is_empty());
+        assert_eq!(Layout::FullWidth, metadata.layout);
+
+        let metadata = Toilet::wideterm().unwrap().metadata();
+        assert_eq!(Some("WideTerm"), metadata.name.as_deref());
+        assert_eq!(Some("Sam Hocevar"), metadata.author.as_deref());
+        assert_eq!(Some("August 2nd, 2007"), metadata.date.as_deref());
+
+        let metadata = Toilet::smblock().unwrap().metadata();
+        assert_eq!(
+            vec!["latin1", "katakana", "blocks"],
+            metadata.character_sets
+        );
+        assert_eq!(Layout::Fitting, metadata.layout);
+
+        let mut font = FIGlet::small().unwrap();
+        font.comments = String::new();
+        let metadata = font.metadata();
+        assert_eq!(
+            (None, None, None),
+            (metadata.name, metadata.author, metadata.license)
+        );
+        assert!(metadata.character_sets.is_empty() && metadata.emails.is_empty());
+
+        font.comments = "Character sets: ISO Latin-1, Greek\nStill missing characters: # <".into();
+        assert_eq!(vec!["ISO Latin-1", "Greek"], font.metadata().character_sets);
+    }
+
+    #[test]
+    fn test_font_builder_from_empty_font() {
+        let mut builder = FontBuilder::new(3, 2).unwrap();
+        builder
+            .comments("Logo by ACME\nfor the splash screen")
+            .layout(Layout::ControlledSmushing(SmushRules::EQUAL))
+            .direction(PrintDirection::RightToLeft);
+        assert!(builder
+            .insert(0xE000, " /\\\n/  \\\n\\__/")
+            .unwrap()
+            .is_none());
+
+        let logo = builder.get(0xE000).unwrap();
+        assert_eq!(vec![" /\\ ", "/  \\", "\\__/"], logo.characters);
+        assert_eq!((4, 3), (logo.width, logo.height));
+
+        match builder.insert('x' as u32, "x\nx").unwrap_err() {
+            Error::InvalidGlyph { code, message } => {
+                assert_eq!('x' as u32, code);
+                assert_eq!("FIGcharacter has 2 rows, the font is 3 rows high", message);
+            }
+            other => panic!("unexpected error {other:?}"),
+        }
+
+        let font = builder.build_figlet();
+        assert_eq!("flf2a$ 3 2 6 1 2 1 129 1", font.header_line.header_line);
+        let metadata = font.metadata();
+        assert_eq!(
+            Layout::ControlledSmushing(SmushRules::EQUAL),
+            metadata.layout
+        );
+        assert_eq!(PrintDirection::RightToLeft, metadata.direction);
+
+        let reloaded = FIGlet::from_content(&font.to_content()).unwrap();
+        assert_eq!(
+            font.fonts[&0xE000].characters,
+            reloaded.fonts[&0xE000].characters
+        );
+
+        let toilet = builder.build_toilet();
+        assert_eq!("tlf2a", toilet.header_line.signature);
+        assert!(toilet
+            .to_content()
+            .starts_with("tlf2a$ 3 2 6 1 2 1 129 1\n"));
+
+        assert!(FontBuilder::new(3, 4).is_err());
+        assert!(FontBuilder::new(0, 0).is_err());
+    }
+
+    #[test]
+    fn test_font_builder_edits_existing_font() {
+        let standard = FIGlet::standard().unwrap();
+        let mut builder = FontBuilder::from_figlet(&standard);
+
+        let old = builder
+            .insert('A' as u32, "AAAAAA\nA\nA\nA\nA\nA")
+            .unwrap()
+            .unwrap();
+        assert_eq!(standard.fonts[&65].characters, old.characters);
+        assert!(builder.remove('B' as u32).is_some());
+        assert!(builder.remove('B' as u32).is_none());
+        builder
+            .insert(0x2665, "   \n<3 \n   \n   \n   \n   ")
+            .unwrap();
+
+        let font = builder.build_figlet();
+        assert_eq!("AAAAAA", font.fonts[&65].characters[0]);
+        assert_eq!("A     ", font.fonts[&65].characters[1]);
+        assert!(!font.fonts.contains_key(&66));
+        assert_eq!(standard.comments, font.comments);
+        // the seven named Deutsch characters are written as codetags as well
+        assert_eq!(
+            Some(font.fonts.len() as i32 - 101 + 7),
+            font.header_line.codetag_count
+        );
+
+        let contents = font.to_content();
+        assert_eq!(
+            Some(font.header_line.header_line.as_str()),
+            contents.lines().next()
+        );
+        assert!(check_font(&contents)
+            .iter()
+            .all(|diagnostic| diagnostic.severity == Severity::Warning));
+        let reloaded = FIGlet::from_content(&contents).unwrap();
+        assert_eq!("<3 ", reloaded.fonts[&0x2665].characters[1]);
+        assert_eq!(0, reloaded.fonts[&66].width);
+    }
+
+    #[test]
+    fn test_font_stack_aligns_fallbacks_on_baseline() {
+        let mut primary = FontBuilder::new(3, 2).unwrap();
+        primary.layout(Layout::FullWidth);
+        primary.insert('A' as u32, "/\\\n||\n  ").unwrap();
+
+        let mut taller = FontBuilder::new(2, 2).unwrap();
+        taller.hardblank('#');
+        taller.insert('A' as u32, "AA\nAA").unwrap();
+        taller.insert('x' as u32, "x#\nxx").unwrap();
+
+        let mut deeper = FontBuilder::new(3, 1).unwrap();
+        deeper.insert('y' as u32, "y\ny\ny").unwrap();
+
+        let stack = FontStack::from_figlet(&primary.build_figlet())
+            .fallback_figlet(&taller.build_figlet())
+            .fallback_toilet(&deeper.build_toilet());
+        assert_eq!(4, stack.header_line().height);
+        assert_eq!(2, stack.header_line().baseline);
+        assert_eq!(
+            vec!["/\\", "||", "  ", "  "],
+            stack.get('A' as u32).unwrap().characters
+        );
+        assert_eq!(
+            vec!["x$", "xx", "  ", "  "],
+            stack.get('x' as u32).unwrap().characters
+        );
+
+        let figure = stack.convert("Axy").unwrap();
+        assert_eq!(4, figure.height);
+        assert_eq!("/\\x  \n||xxy\n    y\n    y\n", figure.as_str());
+
+        let error = stack
+            .render()
+            .missing_character(MissingCharacter::Error)
+            .convert("Az")
+            .unwrap_err();
+        assert_eq!(RenderError::MissingCharacters(vec!['z']), error);
+    }
+
+    #[test]
+    fn test_font_stack_keeps_primary_font() {
+        let slant = FIGlet::slant().unwrap();
+        let stack = FontStack::from_figlet(&slant).fallback_toilet(&Toilet::future().unwrap());
+
+        assert_eq!(
+            slant.convert("Hello").unwrap().as_str(),
+            stack.convert("Hello").unwrap().as_str()
+        );
+        assert!(slant.convert("\u{2500}").is_none());
+        assert!(stack.convert("\u{2500}").is_some());
+    }
+
+    fn render_any_font(font: &Font, message: &str) -> String {
+        font.render().width(120).convert(message).unwrap().as_str()
+    }
+
+    #[test]
+    fn test_font_wraps_figlet_and_toilet() {
+        for builtin in BuiltinFont::ALL {
+            let font = builtin.load().unwrap();
+            assert_eq!(builtin.kind(), font.kind());
+            assert!(font.metadata().height > 0);
+            assert!(!render_any_font(&font, "Test").is_empty());
+        }
+
+        let standard = FIGlet::standard().unwrap();
+        let font = Font::from(standard.clone());
+        assert_eq!(
+            standard.convert("Test").unwrap().as_str(),
+            render_any_font(&font, "Test")
+        );
+        assert_eq!(standard.fonts.len(), font.fonts().len());
+        assert_eq!(
+            standard.fonts[&65].characters,
+            font.get(65).unwrap().characters
+        );
+        assert_eq!(Some("NO-BREAK SPACE"), font.character_name(160));
+        assert_eq!(160, font.character_by_name("NO-BREAK SPACE").unwrap().code);
+        assert_eq!(standard.to_content(), font.to_content());
+        assert_eq!(standard.metadata(), font.metadata());
+
+        let future = Font::from(Toilet::future().unwrap());
+        assert_eq!(FontKind::Toilet, future.kind());
+        assert_eq!("tlf2a", future.header_line().signature);
+        assert!(future.to_content().starts_with("tlf2a"));
+
+        let stack = FontStack::from_font(&font).fallback_font(&future);
+        assert!(stack.convert("\u{2500}").is_some());
+        let rebuilt = FontBuilder::from_font(&future).build(FontKind::Toilet);
+        assert_eq!(
+            render_any_font(&future, "Test"),
+            render_any_font(&rebuilt, "Test")
+        );
+    }
+
+    #[test]
+    fn test_font_detects_format() {
+        let dir = temp_font_dir("detect");
+        let zipped = dir.join("packed.zip");
+        FIGlet::small()
+            .unwrap()
+            .write_file(zipped.to_str().unwrap())
+            .unwrap();
+
+        for (path, kind) in [
+            (PathBuf::from("resources/standard.flf"), FontKind::FIGlet),
+            (PathBuf::from("resources/smblock.tlf"), FontKind::Toilet),
+            (PathBuf::from("resources/mono12.tlf"), FontKind::Toilet),
+            (zipped, FontKind::FIGlet),
+        ] {
+            let font = Font::open(&path).unwrap();
+            assert_eq!(kind, font.kind(), "{}", path.display());
+            assert!(font.convert("Test").is_some());
+        }
+        assert!(matches!(
+            Font::open(dir.join("missing.flf")),
+            Err(Error::Io(_))
+        ));
+
+        let mut latin1 = fixture("resources/small.flf").into_bytes();
+        let start = latin1.iter().position(|byte| *byte == b'\n').unwrap() + 1;
+        latin1[start..start + 2].copy_from_slice(b"\xC4\xD6");
+        let font = Font::from_bytes(&latin1).unwrap();
+        assert_eq!(FontKind::FIGlet, font.kind());
+        assert!(font.comments().starts_with("ÄÖ"));
+        latin1[..5].copy_from_slice(b"tlf2a");
+        assert!(matches!(
+            Font::from_bytes(&latin1),
+            Err(Error::Encoding { .. })
+        ));
+
+        let toilet = Font::from_content(&fixture("resources/future.tlf")).unwrap();
+        assert_eq!(FontKind::Toilet, toilet.kind());
+
+        match Font::from_content("xyz2a$ 1 1 2 -1 0\n").unwrap_err() {
+            Error::UnknownSignature(signature) => assert_eq!("xyz2a$", signature),
+            other => panic!("unexpected error {other:?}"),
+        }
+        let error = Font::from_bytes(b"").unwrap_err();
+        assert_eq!(
+            "unknown font signature \"\", expected flf2a for FIGlet or tlf2a for Toilet fonts",
+            error.to_string()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_toilet_from_content_invalid() {
         assert!(Toilet::from_content("").is_err());
@@ -0,0 +1,241 @@
+use crate::shared::{HeaderLine, Layout, PrintDirection};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// what a font says about itself, read from the header line and, on a best-effort basis, from the
+/// comments
+///
+/// Most fonts follow the conventions of the figlet and toilet font collections, like
+/// `Standard by Glenn Chappell & Ian Chai 3/93` or `This is future.tlf, or “Future”, by Sam
+/// Hocevar.`. Fields which can't be found in the comments are `None` or empty.
+///
+/// ```
+/// use figlet_rs::FIGlet;
+///
+/// let metadata = FIGlet::standard().unwrap().metadata();
+/// assert_eq!(Some("Standard"), metadata.name.as_deref());
+/// assert_eq!(Some("Glenn Chappell & Ian Chai"), metadata.author.as_deref());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontMetadata {
+    /// the name the font calls itself, e.g. `Standard`
+    pub name: Option<String>,
+    /// the original author, without email address
+    pub author: Option<String>,
+    /// the creation date as it is written, e.g. `3/93` or `2006/10/01`
+    pub date: Option<String>,
+    /// the figlet version from the `figlet release` line, e.g. `2.1`
+    pub figlet_release: Option<String>,
+    /// the paragraph with the permission or license text
+    pub license: Option<String>,
+    /// character sets the font mentions, e.g. `ISO Latin-1`
+    pub character_sets: Vec<String>,
+    /// the `Modified by` lines
+    pub modifications: Vec<String>,
+    /// every email address in the comments
+    pub emails: Vec<String>,
+    pub height: u32,
+    pub baseline: u32,
+    pub max_length: u32,
+    pub layout: Layout,
+    pub direction: PrintDirection,
+}
+
+impl FontMetadata {
+    pub(crate) fn new(header_line: &HeaderLine, comments: &str) -> Self {
+        let lines: Vec<&str> = comments
+            .lines()
+            .map(str::trim)
+            .filter(|line| !is_separator(line))
+            .collect();
+
+        let (name, author, title_date) = lines
+            .iter()
+            .find_map(|line| read_title(line))
+            .unwrap_or_default();
+
+        FontMetadata {
+            name,
+            author,
+            date: title_date.or_else(|| lines.iter().find_map(|line| read_date(line))),
+            figlet_release: lines.iter().find_map(|line| {
+                let release = line.strip_prefix("figlet release ")?;
+                release.split_whitespace().next().map(String::from)
+            }),
+            license: read_license(&lines),
+            character_sets: lines
+                .iter()
+                .flat_map(|line| read_character_sets(line))
+                .collect(),
+            modifications: lines
+                .iter()
+                .filter(|line| line.starts_with("Modified "))
+                .map(|line| line.to_string())
+                .collect(),
+            emails: read_emails(comments),
+            height: header_line.height.max(0) as u32,
+            baseline: header_line.baseline.max(0) as u32,
+            max_length: header_line.max_length.max(0) as u32,
+            layout: header_line.layout(),
+            direction: header_line.direction(),
+        }
+    }
+}
+
+/// lines like `=====` which only frame the comments
+fn is_separator(line: &str) -> bool {
+    !line.is_empty() && line.chars().all(|ch| matches!(ch, '=' | '-' | '*' | '#'))
+}
+
+/// name, author and date from a line like `Standard by Glenn Chappell & Ian Chai 3/93 -- ...` or
+/// `This is future.tlf, or “Future”, by Sam Hocevar.`
+fn read_title(line: &str) -> Option<(Option<String>, Option<String>, Option<String>)> {
+    if line.starts_with("Modified ") {
+        return None;
+    }
+    let (before, after) = line.split_once(" by ")?;
+
+    let name = match before.strip_prefix("This is ") {
+        Some(rest) => quoted(rest).or_else(|| {
+            let file = rest
+                .split(|ch: char| ch == ',' || ch.is_whitespace())
+                .next()?;
+            Some(file.split('.').next().unwrap_or(file).to_string())
+        }),
+        None => Some(before.trim().to_string()),
+    }
+    .filter(|name| !name.is_empty());
+
+    let mut author = after.split(" -- ").next().unwrap_or(after);
+    if let Some(index) = author.find(". ") {
+        author = &author[..index];
+    }
+    let mut author = without_emails(author)
+        .trim()
+        .trim_end_matches('.')
+        .to_string();
+
+    let mut date = None;
+    if let Some((rest, last)) = author.rsplit_once(' ') {
+        if looks_like_date(last) {
+            date = Some(last.to_string());
+            author = rest.trim_end().to_string();
+        }
+    }
+
+    let author = Some(author).filter(|author| !author.is_empty());
+    Some((name, author, date))
+}
+
+/// the text between the first pair of quotes
+fn quoted(text: &str) -> Option<String> {
+    let start = text.find(['“', '"'])?;
+    let rest = &text[start..];
+    let rest = &rest[rest.chars().next()?.len_utf8()..];
+    let end = rest.find(['”', '"'])?;
+    Some(rest[..end].to_string())
+}
+
+/// dates like `3/93` or `2006/10/01`
+fn looks_like_date(word: &str) -> bool {
+    word.contains('/') && word.chars().all(|ch| ch.is_ascii_digit() || ch == '/')
+}
+
+/// the first date on a line, like `2006/10/01 -- ...` or `created on August 2nd, 2007.`
+fn read_date(line: &str) -> Option<String> {
+    if let Some(index) = line.find("created on ") {
+        let rest = &line[index + "created on ".len()..];
+        let date = rest
+            .split(". ")
+            .next()
+            .unwrap_or(rest)
+            .trim_end_matches('.');
+        return Some(date.to_string());
+    }
+
+    line.split_whitespace()
+        .find(|word| looks_like_date(word))
+        .map(String::from)
+}
+
+/// the paragraph starting at the first line about permission or a license
+fn read_license(lines: &[&str]) -> Option<String> {
+    let start = lines.iter().position(|line| {
+        let line = line.to_lowercase();
+        line.contains("permission") || line.contains("free software") || line.contains("license")
+    })?;
+
+    let paragraph: Vec<&str> = lines[start..]
+        .iter()
+        .take_while(|line| !line.is_empty())
+        .copied()
+        .collect();
+    Some(paragraph.join(" "))
+}
+
+/// character sets named by lines like `Includes ISO Latin-1`, `Greek characters by ...`,
+/// `to add Latin-{2,3,4,5} support` or `additional characters: latin1, katakana`. Lines about
+/// missing characters are skipped.
+fn read_character_sets(line: &str) -> Vec<String> {
+    let line = line.trim_start();
+    if line.to_ascii_lowercase().contains("missing") {
+        return vec![];
+    }
+
+    let sets = if let Some(sets) = line.strip_prefix("Includes ") {
+        vec![sets]
+    } else if let Some(sets) = strip_character_sets_label(line) {
+        sets.split(',').collect()
+    } else if let Some((set, _)) = line.split_once(" characters by ") {
+        vec![set]
+    } else {
+        line.strip_prefix("to add ")
+            .and_then(|rest| rest.split_once(" support"))
+            .map(|(set, _)| vec![set])
+            .unwrap_or_default()
+    };
+
+    sets.into_iter()
+        .map(|set| set.trim().trim_end_matches('.'))
+        .filter(|set| !set.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// the list after `Characters:` or `Character sets:`, optionally preceded by `additional`
+fn strip_character_sets_label(line: &str) -> Option<&str> {
+    let (label, sets) = line.split_once(':')?;
+    let label = label.to_ascii_lowercase();
+    let label = label.strip_prefix("additional ").unwrap_or(&label);
+    matches!(label, "characters" | "character sets").then(|| sets)
+}
+
+/// every `<name@host>` address
+fn read_emails(comments: &str) -> Vec<String> {
+    let mut emails: Vec<String> = vec![];
+    for part in comments.split('<').skip(1) {
+        let Some((email, _)) = part.split_once('>') else {
+            continue;
+        };
+        let is_email = email.contains('@') && !email.contains(char::is_whitespace);
+        if is_email && !emails.iter().any(|known| known == email) {
+            emails.push(email.to_string());
+        }
+    }
+    emails
+}
+
+/// the text with every `<...>` removed
+fn without_emails(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        result.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find('>')
+            .map_or("", |end| &rest[start + end + 1..]);
+    }
+    result.push_str(rest);
+    result
+}
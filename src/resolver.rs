@@ -0,0 +1,170 @@
+use crate::error::Error;
+use crate::font::Font;
+use crate::shared::FontKind;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::vec::Vec;
+use std::{format, vec};
+
+/// the directories figlet and toilet are usually installed with
+const SYSTEM_DIRS: [&str; 4] = [
+    "/usr/share/figlet",
+    "/usr/local/share/figlet",
+    "/usr/share/figlet/fonts",
+    "/usr/local/share/figlet/fonts",
+];
+
+/// suffixes tried for a bare font name, zip-packaged fonts usually keep the plain suffix
+const SUFFIXES: [(&str, FontKind); 4] = [
+    (".flf", FontKind::FIGlet),
+    (".tlf", FontKind::Toilet),
+    (".flf.zip", FontKind::FIGlet),
+    (".tlf.zip", FontKind::Toilet),
+];
+
+/// a font file found by `FontResolver`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedFont {
+    /// font name without directory and suffix, e.g. `standard`
+    pub name: String,
+    pub path: PathBuf,
+    pub kind: FontKind,
+}
+
+impl ResolvedFont {
+    /// read the font file as a font of the kind its suffix names
+    pub fn load(&self) -> Result<Font, Error> {
+        Font::from_bytes_of_kind(&fs::read(&self.path)?, self.kind)
+    }
+}
+
+/// finds font files by name in a list of directories, like figlet's `-d` and `-f`
+///
+/// ```no_run
+/// use figlet_rs::FontResolver;
+///
+/// let resolver = FontResolver::new().dir("fonts").system_dirs();
+/// let font = resolver.resolve("banner3").unwrap().load().unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FontResolver {
+    dirs: Vec<PathBuf>,
+}
+
+impl FontResolver {
+    /// resolver without any directory
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// search `dir` after the directories added before
+    pub fn dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dirs.push(dir.into());
+        self
+    }
+
+    /// search `FIGLET_FONTDIR` and the usual figlet install locations after the directories
+    /// added before
+    pub fn system_dirs(self) -> Self {
+        self.system_dirs_with(env::var_os("FIGLET_FONTDIR").map(PathBuf::from))
+    }
+
+    /// `system_dirs` with `fontdir` in place of `FIGLET_FONTDIR`
+    pub(crate) fn system_dirs_with(mut self, fontdir: Option<PathBuf>) -> Self {
+        self.dirs.extend(fontdir);
+        self.dirs.extend(SYSTEM_DIRS.iter().map(PathBuf::from));
+        self
+    }
+
+    /// the directories in search order
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// find the font called `name`, trying `.flf`, `.tlf` and the zipped variants in every
+    /// directory. A name with a suffix or a directory part is used as it is.
+    pub fn resolve(&self, name: &str) -> Option<ResolvedFont> {
+        let path = Path::new(name);
+        if path.is_absolute() || path.components().count() > 1 {
+            return candidates(path)
+                .into_iter()
+                .find(|font| font.path.is_file());
+        }
+
+        self.dirs
+            .iter()
+            .flat_map(|dir| candidates(&dir.join(name)))
+            .find(|font| font.path.is_file())
+    }
+
+    /// every font in the directories sorted by name. When several directories have a font of
+    /// the same name, only the one `resolve` would pick is listed.
+    pub fn list(&self) -> Vec<ResolvedFont> {
+        let mut seen = HashSet::new();
+        let mut fonts = vec![];
+        for dir in &self.dirs {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+
+            let mut dir_fonts: Vec<ResolvedFont> = entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .filter_map(|entry| font_file(&entry.path()))
+                .collect();
+            dir_fonts.sort_by_key(|font| suffix_rank(&font.path));
+
+            for font in dir_fonts {
+                if seen.insert(font.name.clone()) {
+                    fonts.push(font);
+                }
+            }
+        }
+
+        fonts.sort_by(|a, b| a.name.cmp(&b.name));
+        fonts
+    }
+}
+
+/// the path itself when it has a font suffix, otherwise the path with every suffix
+fn candidates(path: &Path) -> Vec<ResolvedFont> {
+    if let Some(font) = font_file(path) {
+        return vec![font];
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    SUFFIXES
+        .iter()
+        .map(|(suffix, kind)| ResolvedFont {
+            name: name.clone(),
+            path: path.with_file_name(format!("{name}{suffix}")),
+            kind: *kind,
+        })
+        .collect()
+}
+
+fn font_file(path: &Path) -> Option<ResolvedFont> {
+    let file_name = path.file_name()?.to_str()?;
+    SUFFIXES.iter().find_map(|(suffix, kind)| {
+        let name = file_name.strip_suffix(suffix)?;
+        (!name.is_empty()).then(|| ResolvedFont {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            kind: *kind,
+        })
+    })
+}
+
+fn suffix_rank(path: &Path) -> usize {
+    let file_name = path.to_string_lossy();
+    SUFFIXES
+        .iter()
+        .position(|(suffix, _)| file_name.ends_with(suffix))
+        .unwrap_or(SUFFIXES.len())
+}
@@ -1,7 +1,19 @@
-use std::collections::HashMap;
-use std::fmt;
+use crate::check::{check_font, Diagnostic, Severity};
+use crate::control::ControlFile;
+use crate::encoding::{decode_bytes, Encoding};
+use crate::error::Error;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+#[cfg(feature = "std")]
 use std::fs;
+#[cfg(feature = "std")]
 use std::io::{Cursor, Read};
+#[cfg(feature = "std")]
+use zip::result::ZipError;
 
 pub(crate) const SM_EQUAL: i32 = 1;
 pub(crate) const SM_LOWLINE: i32 = 2;
@@ -12,32 +24,238 @@ pub(crate) const SM_HARDBLANK: i32 = 32;
 pub(crate) const SM_KERN: i32 = 64;
 pub(crate) const SM_SMUSH: i32 = 128;
 
+pub(crate) const SM_VERTICAL_EQUAL: i32 = 256;
+pub(crate) const SM_VERTICAL_LOWLINE: i32 = 512;
+pub(crate) const SM_VERTICAL_HIERARCHY: i32 = 1024;
+pub(crate) const SM_HORIZONTAL_LINE: i32 = 2048;
+pub(crate) const SM_VERTICAL_LINE: i32 = 4096;
+pub(crate) const SM_VERTICAL_KERN: i32 = 8192;
+pub(crate) const SM_VERTICAL_SMUSH: i32 = 16384;
+
+const SM_VERTICAL_RULES: i32 = SM_VERTICAL_EQUAL
+    | SM_VERTICAL_LOWLINE
+    | SM_VERTICAL_HIERARCHY
+    | SM_HORIZONTAL_LINE
+    | SM_VERTICAL_LINE;
+
+/// whether a font is a FIGlet `.flf` or a Toilet `.tlf` font
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FontKind {
+    FIGlet,
+    Toilet,
+}
+
+impl FontKind {
+    /// the kind of font the signature at the start of the header line names, `flf2` for FIGlet
+    /// and `tlf2` for Toilet fonts
+    pub(crate) fn detect(contents: &[u8]) -> Result<FontKind, Error> {
+        if contents.starts_with(b"flf2") {
+            return Ok(FontKind::FIGlet);
+        }
+        if contents.starts_with(b"tlf2") {
+            return Ok(FontKind::Toilet);
+        }
+
+        let first = contents
+            .split(|byte| byte.is_ascii_whitespace())
+            .next()
+            .unwrap_or_default();
+        let signature = String::from_utf8_lossy(&first[..first.len().min(16)]);
+        Err(Error::UnknownSignature(signature.into_owned()))
+    }
+}
+
+/// codes of the 102 FIGcharacters every font has in this order: ASCII 32 to 126, then the seven
+/// Deutsch characters
+pub(crate) const REQUIRED_CODES: [u32; 102] = required_codes();
+
+const fn required_codes() -> [u32; 102] {
+    let deutsch = [196, 214, 220, 228, 246, 252, 223];
+    let mut codes = [0; 102];
+    let mut i = 0;
+    while i < 102 {
+        codes[i] = if i < 95 {
+            32 + i as u32
+        } else {
+            deutsch[i - 95]
+        };
+        i += 1;
+    }
+    codes
+}
+
+#[derive(Debug)]
 pub(crate) struct FontData {
     pub header_line: HeaderLine,
     pub comments: String,
-    pub fonts: HashMap<u32, FIGcharacter>,
+    pub fonts: BTreeMap<u32, FIGcharacter>,
 }
 
-pub(crate) fn load_font_file(filename: &str) -> Result<FontData, String> {
-    let bytes = fs::read(filename).map_err(|e| format!("{e:?}"))?;
-    parse_font_bytes(&bytes)
+/// a deserialized FIGlet or Toilet font, which is checked with `FontData::validate` before use
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+pub(crate) struct UncheckedFont {
+    header_line: HeaderLine,
+    comments: String,
+    fonts: BTreeMap<u32, FIGcharacter>,
 }
 
-pub(crate) fn parse_font_bytes(bytes: &[u8]) -> Result<FontData, String> {
-    let contents = decode_font_bytes(bytes)?;
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedFont> for FontData {
+    type Error = Error;
+
+    fn try_from(font: UncheckedFont) -> Result<Self, Self::Error> {
+        FontData {
+            header_line: font.header_line,
+            comments: font.comments,
+            fonts: font.fonts,
+        }
+        .validate()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl FontData {
+    /// check a font which wasn't parsed from a font file, e.g. a deserialized one, so that every
+    /// FIGcharacter can be rendered
+    pub(crate) fn validate(self) -> Result<FontData, Error> {
+        check_header_fields(&self.header_line)?;
+
+        let height = self.header_line.height as usize;
+        for (code, font) in &self.fonts {
+            let invalid = |message: String| Error::InvalidGlyph {
+                code: *code,
+                message,
+            };
+            if font.code != *code {
+                return Err(invalid(format!(
+                    "FIGcharacter {} is stored as {code}",
+                    font.code
+                )));
+            }
+            if font.characters.len() != height || font.height as usize != height {
+                return Err(invalid(format!(
+                    "FIGcharacter has {} rows and height {}, the font is {height} rows high",
+                    font.characters.len(),
+                    font.height
+                )));
+            }
+            let width = font.characters[0].chars().count();
+            if font.width as usize != width {
+                return Err(invalid(format!(
+                    "FIGcharacter has width {}, but its first row is {width} characters wide",
+                    font.width
+                )));
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+/// load a font file, see `parse_font_bytes` for `encoding`
+#[cfg(feature = "std")]
+pub(crate) fn load_font_file(
+    filename: &str,
+    encoding: Option<Encoding>,
+) -> Result<FontData, Error> {
+    let bytes = fs::read(filename)?;
+    parse_font_bytes(&bytes, encoding)
+}
+
+/// parse a plain or zip-packaged font, the encoding is detected when `encoding` is `None`
+pub(crate) fn parse_font_bytes(
+    bytes: &[u8],
+    encoding: Option<Encoding>,
+) -> Result<FontData, Error> {
+    let contents = decode_font_bytes(bytes, encoding)?;
     parse_font_content(&contents)
 }
 
-pub(crate) fn parse_font_content(contents: &str) -> Result<FontData, String> {
+pub(crate) fn parse_font_content(contents: &str) -> Result<FontData, Error> {
+    read_font(contents, &mut Recovery::default())
+}
+
+/// parse a font in the given mode, and return the warnings along with it
+pub(crate) fn parse_font_content_with_mode(
+    contents: &str,
+    mode: ParseMode,
+) -> Result<(FontData, Vec<Diagnostic>), Error> {
+    match mode {
+        ParseMode::Strict => {
+            let (errors, warnings): (Vec<Diagnostic>, Vec<Diagnostic>) = check_font(contents)
+                .into_iter()
+                .partition(|diagnostic| diagnostic.severity == Severity::Error);
+            if !errors.is_empty() {
+                return Err(Error::Invalid(errors));
+            }
+            Ok((parse_font_content(contents)?, warnings))
+        }
+        ParseMode::Lenient => {
+            let mut warnings = vec![];
+            let data = read_font(
+                contents,
+                &mut Recovery {
+                    warnings: Some(&mut warnings),
+                },
+            )?;
+            Ok((data, warnings))
+        }
+    }
+}
+
+/// see `parse_font_bytes` and `parse_font_content_with_mode`
+pub(crate) fn parse_font_bytes_with_mode(
+    bytes: &[u8],
+    encoding: Option<Encoding>,
+    mode: ParseMode,
+) -> Result<(FontData, Vec<Diagnostic>), Error> {
+    let contents = decode_font_bytes(bytes, encoding)?;
+    parse_font_content_with_mode(&contents, mode)
+}
+
+/// how strictly a font is checked while it is parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// reject every font `check_font` reports an error for
+    Strict,
+    /// skip FIGcharacters and codetags which can't be read, and report them as warnings
+    Lenient,
+}
+
+/// decides whether a broken FIGcharacter or codetag fails the whole font
+#[derive(Default)]
+struct Recovery<'a> {
+    /// the skipped problems in lenient mode, `None` fails at the first problem
+    warnings: Option<&'a mut Vec<Diagnostic>>,
+}
+
+impl<'a> Recovery<'a> {
+    /// `Ok(None)` when the problem was skipped
+    fn recover<T>(&mut self, result: Result<T, Error>) -> Result<Option<T>, Error> {
+        match (result, self.warnings.as_deref_mut()) {
+            (Ok(value), _) => Ok(Some(value)),
+            (Err(e), Some(warnings)) => {
+                warnings.push(Diagnostic::warning(e));
+                Ok(None)
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+}
+
+fn read_font(contents: &str, recovery: &mut Recovery) -> Result<FontData, Error> {
     let lines: Vec<&str> = contents.lines().collect();
 
     if lines.is_empty() {
-        return Err("can not generate FIGlet font from empty string".to_string());
+        return Err(Error::Header(
+            "can not generate FIGlet font from empty string".to_string(),
+        ));
     }
 
     let header_line = read_header_line(lines.first().unwrap())?;
     let comments = read_comments(&lines, header_line.comment_lines)?;
-    let fonts = read_fonts(&lines, &header_line)?;
+    let fonts = read_fonts(&lines, &header_line, recovery)?;
 
     Ok(FontData {
         header_line,
@@ -46,33 +264,59 @@ pub(crate) fn parse_font_content(contents: &str) -> Result<FontData, String> {
     })
 }
 
-fn decode_font_bytes(bytes: &[u8]) -> Result<String, String> {
+pub(crate) fn decode_font_bytes(bytes: &[u8], encoding: Option<Encoding>) -> Result<String, Error> {
+    decode_bytes(&unpack_font_bytes(bytes)?, encoding)
+}
+
+/// the first file of a zip-packaged font, or the bytes themselves
+pub(crate) fn unpack_font_bytes(bytes: &[u8]) -> Result<Cow<'_, [u8]>, Error> {
+    #[cfg(feature = "std")]
     if bytes.starts_with(b"PK\x03\x04") {
-        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("{e:?}"))?;
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
         if archive.is_empty() {
-            return Err("zip font archive is empty".to_string());
+            return Err(Error::Zip(ZipError::FileNotFound));
         }
 
-        let mut file = archive.by_index(0).map_err(|e| format!("{e:?}"))?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)
-            .map_err(|e| format!("{e:?}"))?;
-        Ok(contents)
-    } else {
-        String::from_utf8(bytes.to_vec()).map_err(|e| format!("{e:?}"))
+        let mut file = archive.by_index(0)?;
+        let mut contents = vec![];
+        file.read_to_end(&mut contents)?;
+        return Ok(Cow::Owned(contents));
     }
+
+    Ok(Cow::Borrowed(bytes))
 }
 
-fn read_header_line(header_line: &str) -> Result<HeaderLine, String> {
-    HeaderLine::try_from(header_line)
+/// the header line, with the fields the FIGcharacters are read with checked
+fn read_header_line(header_line: &str) -> Result<HeaderLine, Error> {
+    let header_line = HeaderLine::try_from(header_line)?;
+    check_header_fields(&header_line)?;
+    Ok(header_line)
 }
 
-fn read_comments(lines: &[&str], comment_count: i32) -> Result<String, String> {
-    let length = lines.len() as i32;
-    if length < comment_count + 1 {
-        Err("can't get comments from font".to_string())
-    } else {
-        Ok(lines[1..(1 + comment_count) as usize].join("\n"))
+/// the fields of the header line the FIGcharacters are read and rendered with
+fn check_header_fields(header_line: &HeaderLine) -> Result<(), Error> {
+    if header_line.height < 1 {
+        return Err(Error::Header(format!(
+            "height {} is less than 1",
+            header_line.height
+        )));
+    }
+    if header_line.max_length < 0 {
+        return Err(Error::Header(format!(
+            "max_length {} is negative",
+            header_line.max_length
+        )));
+    }
+
+    Ok(())
+}
+
+fn read_comments(lines: &[&str], comment_count: i32) -> Result<String, Error> {
+    match usize::try_from(comment_count) {
+        Ok(count) if count < lines.len() => Ok(lines[1..1 + count].join("\n")),
+        _ => Err(Error::Header(format!(
+            "can't get {comment_count} comment lines from font"
+        ))),
     }
 }
 
@@ -103,12 +347,19 @@ fn extract_one_font(
     code: u32,
     start_index: usize,
     height: usize,
-) -> Result<FIGcharacter, String> {
+) -> Result<FIGcharacter, Error> {
     let mut characters = vec![];
     for i in 0..height {
         let index = start_index + i;
         let is_last_index = i == height - 1;
-        characters.push(extract_one_line(lines, index, height, is_last_index)?);
+        let line = extract_one_line(lines, index, height, is_last_index).map_err(|message| {
+            Error::Glyph {
+                line: index + 1,
+                code,
+                message,
+            }
+        })?;
+        characters.push(line);
     }
 
     Ok(FIGcharacter {
@@ -116,52 +367,50 @@ fn extract_one_font(
         width: characters[0].chars().count() as u32,
         height: height as u32,
         characters,
+        name: None,
     })
 }
 
 fn read_required_font(
     lines: &[&str],
     headerline: &HeaderLine,
-    map: &mut HashMap<u32, FIGcharacter>,
-) -> Result<(), String> {
-    let offset = (1 + headerline.comment_lines) as usize;
+    map: &mut BTreeMap<u32, FIGcharacter>,
+    recovery: &mut Recovery,
+) -> Result<(), Error> {
+    let offset = 1 + headerline.comment_lines as usize;
     let height = headerline.height as usize;
     let size = lines.len();
 
-    for i in 0..=94 {
-        let code = (i + 32) as u32;
-        let start_index = offset + i * height;
+    for (i, code) in REQUIRED_CODES.iter().enumerate() {
+        let start_index = offset.saturating_add(i.saturating_mul(height));
         if start_index >= size {
             break;
         }
 
-        let font = extract_one_font(lines, code, start_index, height)?;
-        map.insert(code, font);
-    }
-
-    let offset = offset + 95 * height;
-    let required_deutsch_characters_codes: [u32; 7] = [196, 214, 220, 228, 246, 252, 223];
-    for (i, code) in required_deutsch_characters_codes.iter().enumerate() {
-        let start_index = offset + i * height;
-        if start_index >= size {
-            break;
+        if let Some(font) = recovery.recover(extract_one_font(lines, *code, start_index, height))? {
+            map.insert(*code, font);
         }
-
-        let font = extract_one_font(lines, *code, start_index, height)?;
-        map.insert(*code, font);
     }
 
     Ok(())
 }
 
-fn extract_codetag_font_code(lines: &[&str], index: usize) -> Result<Option<u32>, String> {
+pub(crate) fn extract_codetag_font_code(
+    lines: &[&str],
+    index: usize,
+) -> Result<Option<u32>, Error> {
+    let codetag_error = |message: String| Error::Codetag {
+        line: index + 1,
+        message,
+    };
+
     let line = lines
         .get(index)
-        .ok_or_else(|| "get codetag line error".to_string())?;
+        .ok_or_else(|| codetag_error("missing codetag line".to_string()))?;
 
     let infos: Vec<&str> = line.split_whitespace().collect();
     if infos.is_empty() {
-        return Err("extract code for codetag font error".to_string());
+        return Err(codetag_error("codetag line has no code".to_string()));
     }
 
     let code = infos[0].trim();
@@ -177,47 +426,66 @@ fn extract_codetag_font_code(lines: &[&str], index: usize) -> Result<Option<u32>
     } else {
         unsigned.parse()
     }
-    .map_err(|e| format!("{e:?}"))?;
+    .map_err(|e| codetag_error(format!("can't parse code {code}: {e}")))?;
 
     if is_negative {
         Ok(None)
     } else {
         u32::try_from(parsed)
             .map(Some)
-            .map_err(|e| format!("{e:?}"))
+            .map_err(|e| codetag_error(format!("code {code} is out of range: {e}")))
     }
 }
 
+/// the description after the code of a codetag line, like `LATIN CAPITAL LETTER A WITH
+/// DIAERESIS` in `196  LATIN CAPITAL LETTER A WITH DIAERESIS`
+fn extract_codetag_name(line: &str) -> Option<String> {
+    let (_, name) = line.trim().split_once(char::is_whitespace)?;
+    Some(name.trim().to_string()).filter(|name| !name.is_empty())
+}
+
 fn read_codetag_font(
     lines: &[&str],
     headerline: &HeaderLine,
-    map: &mut HashMap<u32, FIGcharacter>,
-) -> Result<(), String> {
-    let offset = (1 + headerline.comment_lines + 102 * headerline.height) as usize;
+    map: &mut BTreeMap<u32, FIGcharacter>,
+    recovery: &mut Recovery,
+) -> Result<(), Error> {
+    let height = headerline.height as usize;
+    let offset = (1 + headerline.comment_lines as usize)
+        .saturating_add(REQUIRED_CODES.len().saturating_mul(height));
     if offset >= lines.len() {
         return Ok(());
     }
 
-    let codetag_height = (headerline.height + 1) as usize;
+    let codetag_height = height + 1;
     let codetag_lines = lines.len() - offset;
 
-    if codetag_lines % codetag_height != 0 {
-        return Err("codetag font is illegal.".to_string());
-    }
-
     let size = codetag_lines / codetag_height;
 
+    // in lenient mode a FIGcharacter with a broken codetag or row is skipped, and the trailing
+    // lines are ignored
     for i in 0..size {
         let start_index = offset + i * codetag_height;
-        if start_index >= lines.len() {
-            break;
-        }
-
-        let Some(code) = extract_codetag_font_code(lines, start_index)? else {
+        let Some(Some(code)) = recovery.recover(extract_codetag_font_code(lines, start_index))?
+        else {
             continue;
         };
-        let font = extract_one_font(lines, code, start_index + 1, headerline.height as usize)?;
-        map.insert(code, font);
+        let font = extract_one_font(lines, code, start_index + 1, height);
+        if let Some(mut font) = recovery.recover(font)? {
+            font.name = extract_codetag_name(lines[start_index]);
+            map.insert(code, font);
+        }
+    }
+
+    if codetag_lines % codetag_height != 0 {
+        recovery.recover::<()>(Err(Error::Codetag {
+            line: offset + size * codetag_height + 1,
+            message: format!(
+                "{} trailing lines don't make up a FIGcharacter of height {}",
+                codetag_lines % codetag_height,
+                headerline.height
+            ),
+        }))?;
     }
 
     Ok(())
@@ -226,44 +494,713 @@ fn read_codetag_font(
 fn read_fonts(
     lines: &[&str],
     headerline: &HeaderLine,
-) -> Result<HashMap<u32, FIGcharacter>, String> {
-    let mut map = HashMap::new();
-    read_required_font(lines, headerline, &mut map)?;
-    read_codetag_font(lines, headerline, &mut map)?;
+    recovery: &mut Recovery,
+) -> Result<BTreeMap<u32, FIGcharacter>, Error> {
+    let mut map = BTreeMap::new();
+    read_required_font(lines, headerline, &mut map, recovery)?;
+    read_codetag_font(lines, headerline, &mut map, recovery)?;
     Ok(map)
 }
 
+/// options used to render a message, the default follows the settings of the font
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    /// maximum width of the output in columns, like figlet's `-w`.
+    ///
+    /// A row of text is broken at the last space that fits, and a word which is too wide on its
+    /// own is broken between characters. `None` means the output is never wrapped.
+    pub width: Option<usize>,
+    /// horizontal alignment of every row of text
+    pub justification: Justification,
+    /// horizontal layout, which overrides the layout of the font header for this render only
+    pub layout: Layout,
+    /// print direction, which overrides `HeaderLine::print_direction` for this render only
+    pub direction: PrintDirection,
+    /// what to do with characters the font doesn't define
+    pub missing_character: MissingCharacter,
+    /// paragraph mode, like figlet's `-p`. A newline becomes a space unless a blank line or a
+    /// line starting with whitespace follows, which works best together with `width`.
+    pub paragraph: bool,
+    /// control files applied in order to every character before it is looked up in the font,
+    /// like figlet's `-C`
+    pub control_files: Vec<ControlFile>,
+}
+
+/// order in which FIGcharacters are put next to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrintDirection {
+    /// the print direction of the font header, like figlet's `-X`
+    #[default]
+    Default,
+    /// like figlet's `-L`
+    LeftToRight,
+    /// like figlet's `-R`
+    RightToLeft,
+}
+
+impl PrintDirection {
+    fn is_right_to_left(self, header_line: &HeaderLine) -> bool {
+        match self {
+            PrintDirection::Default => header_line.is_right_to_left(),
+            PrintDirection::LeftToRight => false,
+            PrintDirection::RightToLeft => true,
+        }
+    }
+}
+
+/// horizontal layout used to put FIGcharacters next to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// the layout of the font header, like figlet's `-s`
+    #[default]
+    Default,
+    /// every FIGcharacter keeps its full width, like figlet's `-W`
+    FullWidth,
+    /// FIGcharacters are moved together until they touch, like figlet's `-k`
+    Fitting,
+    /// FIGcharacters overlap by one column and the later sub-character wins, like figlet's `-S`
+    UniversalSmushing,
+    /// FIGcharacters are smushed with the given rules only, like figlet's `-m`
+    ControlledSmushing(SmushRules),
+}
+
+impl Layout {
+    fn horizontal_layout(self, header_line: &HeaderLine) -> i32 {
+        match self {
+            Layout::Default => header_line.effective_layout(),
+            Layout::FullWidth => 0,
+            Layout::Fitting => SM_KERN,
+            Layout::UniversalSmushing => SM_SMUSH,
+            Layout::ControlledSmushing(rules) => rules.bits() | SM_SMUSH,
+        }
+    }
+}
+
+/// set of horizontal smushing rules, combine them with `|`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SmushRules(i32);
+
+impl SmushRules {
+    /// two equal sub-characters are smushed into one
+    pub const EQUAL: SmushRules = SmushRules(SM_EQUAL);
+    /// an underscore is replaced by `|/\[]{}()<>`
+    pub const LOWLINE: SmushRules = SmushRules(SM_LOWLINE);
+    /// the sub-character of the higher class in `|`, `/\`, `[]`, `{}`, `()`, `<>` wins
+    pub const HIERARCHY: SmushRules = SmushRules(SM_HIERARCHY);
+    /// opposite brackets, braces and parentheses are smushed into `|`
+    pub const PAIR: SmushRules = SmushRules(SM_PAIR);
+    /// `/\` becomes `|`, `\/` becomes `Y` and `><` becomes `X`
+    pub const BIGX: SmushRules = SmushRules(SM_BIGX);
+    /// two hardblanks are smushed into one
+    pub const HARDBLANK: SmushRules = SmushRules(SM_HARDBLANK);
+    /// every horizontal smushing rule
+    pub const ALL: SmushRules =
+        SmushRules(SM_EQUAL | SM_LOWLINE | SM_HIERARCHY | SM_PAIR | SM_BIGX | SM_HARDBLANK);
+
+    /// the rules as the bits used by `HeaderLine::full_layout`
+    pub fn bits(self) -> i32 {
+        self.0
+    }
+
+    /// whether every rule of `other` is part of these rules
+    pub fn contains(self, other: SmushRules) -> bool {
+        (self.0 & other.0) == other.0
+    }
+}
+
+impl core::ops::BitOr for SmushRules {
+    type Output = SmushRules;
+
+    fn bitor(self, rhs: SmushRules) -> SmushRules {
+        SmushRules(self.0 | rhs.0)
+    }
+}
+
+/// builder which gathers every rendering option, created by `FIGlet::render` or `Toilet::render`
+///
+/// ```
+/// use figlet_rs::{FIGlet, Layout};
+///
+/// let font = FIGlet::standard().unwrap();
+/// let figure = font
+///     .render()
+///     .width(80)
+///     .center()
+///     .layout(Layout::Fitting)
+///     .convert("Hello Rust")
+///     .unwrap();
+/// assert!(figure.as_str().lines().all(|row| row.chars().count() <= 80));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RenderBuilder<'a> {
+    header_line: &'a HeaderLine,
+    fonts: &'a BTreeMap<u32, FIGcharacter>,
+    options: RenderOptions,
+}
+
+impl<'a> RenderBuilder<'a> {
+    pub(crate) fn new(header_line: &'a HeaderLine, fonts: &'a BTreeMap<u32, FIGcharacter>) -> Self {
+        Self {
+            header_line,
+            fonts,
+            options: RenderOptions::default(),
+        }
+    }
+
+    /// replace every option at once
+    pub fn options(mut self, options: RenderOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// maximum width of the output in columns, see `RenderOptions::width`
+    pub fn width(mut self, width: usize) -> Self {
+        self.options.width = Some(width);
+        self
+    }
+
+    /// horizontal alignment of every row of text, see `RenderOptions::justification`
+    pub fn justification(mut self, justification: Justification) -> Self {
+        self.options.justification = justification;
+        self
+    }
+
+    /// shortcut for `justification(Justification::Left)`
+    pub fn left(self) -> Self {
+        self.justification(Justification::Left)
+    }
+
+    /// shortcut for `justification(Justification::Center)`
+    pub fn center(self) -> Self {
+        self.justification(Justification::Center)
+    }
+
+    /// shortcut for `justification(Justification::Right)`
+    pub fn right(self) -> Self {
+        self.justification(Justification::Right)
+    }
+
+    /// horizontal layout for this render only, see `RenderOptions::layout`
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.options.layout = layout;
+        self
+    }
+
+    /// print direction for this render only, see `RenderOptions::direction`
+    pub fn direction(mut self, direction: PrintDirection) -> Self {
+        self.options.direction = direction;
+        self
+    }
+
+    /// what to do with characters the font doesn't define, see `RenderOptions::missing_character`
+    pub fn missing_character(mut self, missing_character: MissingCharacter) -> Self {
+        self.options.missing_character = missing_character;
+        self
+    }
+
+    /// paragraph mode, see `RenderOptions::paragraph`
+    pub fn paragraph(mut self, paragraph: bool) -> Self {
+        self.options.paragraph = paragraph;
+        self
+    }
+
+    /// add a control file, which is applied after the ones added before
+    pub fn control_file(mut self, control_file: ControlFile) -> Self {
+        self.options.control_files.push(control_file);
+        self
+    }
+
+    /// the options gathered so far
+    pub fn render_options(&self) -> &RenderOptions {
+        &self.options
+    }
+
+    /// convert string literal to FIGure with the gathered options
+    pub fn convert(&self, message: &str) -> Result<FIGure<'a>, RenderError> {
+        render(self.header_line, self.fonts, message, &self.options)
+    }
+}
+
+/// what to do with a character of the message which the font doesn't define
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingCharacter {
+    /// leave the character out, like figlet does
+    #[default]
+    Skip,
+    /// use the FIGcharacter with code 0 as the FIGfont spec intends, or skip the character when
+    /// the font has none
+    CodeZero,
+    /// draw a box as high as the font
+    Placeholder,
+    /// fail with `RenderError::MissingCharacters`
+    Error,
+}
+
+/// why a message could not be rendered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderError {
+    /// the message is empty or none of its characters could be rendered
+    Empty,
+    /// every distinct character of the message the font can't render, in order of appearance
+    MissingCharacters(Vec<char>),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::Empty => write!(f, "nothing to render"),
+            RenderError::MissingCharacters(characters) => {
+                write!(f, "font can't render characters:")?;
+                for ch in characters {
+                    write!(f, " {ch:?}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RenderError {}
+
+/// horizontal alignment of the rendered rows of text, like figlet's `-x`, `-l`, `-c` and `-r`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Justification {
+    /// left for left-to-right fonts and right for right-to-left fonts, see
+    /// [`HeaderLine::print_direction`]
+    #[default]
+    Auto,
+    Left,
+    Center,
+    Right,
+}
+
 pub(crate) fn render<'a>(
     header_line: &'a HeaderLine,
-    fonts: &'a HashMap<u32, FIGcharacter>,
+    fonts: &'a BTreeMap<u32, FIGcharacter>,
     message: &str,
-) -> Option<FIGure<'a>> {
+    options: &RenderOptions,
+) -> Result<FIGure<'a>, RenderError> {
     if message.is_empty() {
-        return None;
+        return Err(RenderError::Empty);
     }
 
-    let mut characters: Vec<&FIGcharacter> = vec![];
-    for ch in message.chars() {
-        let code = ch as u32;
-        if let Some(character) = fonts.get(&code) {
-            characters.push(character);
-        }
+    let message_lines = if options.paragraph {
+        paragraph_lines(message)
+    } else {
+        message.lines().map(Cow::Borrowed).collect()
+    };
+
+    let mut missing = vec![];
+    let text_lines: Vec<Vec<Cow<'a, FIGcharacter>>> = message_lines
+        .iter()
+        .map(|text_line| {
+            text_line
+                .chars()
+                .filter_map(|ch| lookup_character(header_line, fonts, ch, options, &mut missing))
+                .collect()
+        })
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(RenderError::MissingCharacters(missing));
+    }
+    if text_lines.iter().all(Vec::is_empty) {
+        return Err(RenderError::Empty);
     }
 
-    if characters.is_empty() {
-        return None;
+    let mut blocks: Vec<Vec<String>> = vec![];
+    for text_line in &text_lines {
+        let line_characters: Vec<&FIGcharacter> = text_line.iter().map(Cow::as_ref).collect();
+        match options.width {
+            Some(width) => {
+                for row in wrap_characters(header_line, options, &line_characters, width) {
+                    blocks.push(Renderer::new(header_line, options).render(&row));
+                }
+            }
+            None => blocks.push(Renderer::new(header_line, options).render(&line_characters)),
+        }
     }
 
-    let rendered_lines = Renderer::new(header_line, fonts).render(&characters);
+    let characters: Vec<Cow<'a, FIGcharacter>> = text_lines.into_iter().flatten().collect();
+    justify_blocks(header_line, &mut blocks, options);
+    let rendered_lines = stack_blocks(header_line.vertical_layout(), blocks);
 
-    Some(FIGure {
+    Ok(FIGure {
         characters,
         height: header_line.height as u32,
         lines: rendered_lines,
     })
 }
 
+/// join lines the way figlet's `-p` does: a newline becomes a space, unless the next character
+/// is whitespace. Blank lines and lines starting with whitespace therefore start a new row of
+/// text, and the line after a blank line keeps the space its newline became.
+fn paragraph_lines(message: &str) -> Vec<Cow<'_, str>> {
+    let mut rows: Vec<Cow<str>> = vec![];
+    for line in message.lines() {
+        let starts_row = line.chars().next().map_or(true, char::is_whitespace);
+        match rows.last_mut() {
+            Some(row) if !starts_row => {
+                let row = row.to_mut();
+                row.push(' ');
+                row.push_str(line);
+            }
+            _ => rows.push(Cow::Borrowed(line)),
+        }
+    }
+
+    rows
+}
+
+fn lookup_character<'a>(
+    header_line: &HeaderLine,
+    fonts: &'a BTreeMap<u32, FIGcharacter>,
+    ch: char,
+    options: &RenderOptions,
+    missing: &mut Vec<char>,
+) -> Option<Cow<'a, FIGcharacter>> {
+    let code = options
+        .control_files
+        .iter()
+        .fold(ch as u32, |code, control_file| control_file.translate(code));
+    if let Some(character) = fonts.get(&code) {
+        return Some(Cow::Borrowed(character));
+    }
+
+    match options.missing_character {
+        MissingCharacter::Skip => None,
+        MissingCharacter::CodeZero => fonts.get(&0).map(Cow::Borrowed),
+        MissingCharacter::Placeholder => Some(Cow::Owned(placeholder_character(header_line, code))),
+        MissingCharacter::Error => {
+            if !missing.contains(&ch) {
+                missing.push(ch);
+            }
+            None
+        }
+    }
+}
+
+/// a box from the top row down to the baseline, so it lines up with the other FIGcharacters
+fn placeholder_character(header_line: &HeaderLine, code: u32) -> FIGcharacter {
+    let height = header_line.height.max(0) as usize;
+    let box_height = (header_line.baseline.max(1) as usize).min(height);
+
+    let characters: Vec<String> = (0..height)
+        .map(|row| {
+            let line = match (box_height, row) {
+                (1, 0) => "[]",
+                (_, row) if row == 0 || row + 1 == box_height => "+-+",
+                (_, row) if row < box_height => "| |",
+                _ => "   ",
+            };
+            line.to_string()
+        })
+        .collect();
+
+    FIGcharacter {
+        code,
+        width: characters
+            .first()
+            .map_or(0, |line| line.chars().count() as u32),
+        height: height as u32,
+        characters,
+        name: None,
+    }
+}
+
+fn block_width(block: &[String]) -> usize {
+    block
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
+/// pad every rendered row of text so it is aligned inside the output width, which is the
+/// configured width or else the widest row of text. The blocks are already rendered, so the
+/// hardblanks are gone and the padding never takes part in smushing.
+fn justify_blocks(header_line: &HeaderLine, blocks: &mut [Vec<String>], options: &RenderOptions) {
+    let justification = match options.justification {
+        Justification::Auto if options.direction.is_right_to_left(header_line) => {
+            Justification::Right
+        }
+        Justification::Auto => Justification::Left,
+        justification => justification,
+    };
+    if justification == Justification::Left {
+        return;
+    }
+
+    let width = options.width.unwrap_or_else(|| {
+        blocks
+            .iter()
+            .map(|block| block_width(block))
+            .max()
+            .unwrap_or(0)
+    });
+
+    for block in blocks.iter_mut() {
+        let free = width.saturating_sub(block_width(block));
+        let padding = match justification {
+            Justification::Center => free / 2,
+            _ => free,
+        };
+        if padding == 0 {
+            continue;
+        }
+
+        let prefix = " ".repeat(padding);
+        for line in block.iter_mut() {
+            line.insert_str(0, &prefix);
+        }
+    }
+}
+
+fn smush_lowline(left: char, right: char) -> Option<char> {
+    if left == '_' && "|/\\[]{}()<>".contains(right) {
+        return Some(right);
+    }
+    if right == '_' && "|/\\[]{}()<>".contains(left) {
+        return Some(left);
+    }
+    None
+}
+
+fn smush_hierarchy(left: char, right: char) -> Option<char> {
+    for (a, b) in [
+        ("|", "/\\[]{}()<>"),
+        ("/\\", "[]{}()<>"),
+        ("[]", "{}()<>"),
+        ("{}", "()<>"),
+        ("()", "<>"),
+    ] {
+        if a.contains(left) && b.contains(right) {
+            return Some(right);
+        }
+        if a.contains(right) && b.contains(left) {
+            return Some(left);
+        }
+    }
+    None
+}
+
+/// smush the sub-character `upper` with the sub-character `lower` right below it
+pub(crate) fn vertical_smush_chars(layout: i32, upper: char, lower: char) -> Option<char> {
+    if upper == ' ' {
+        return Some(lower);
+    }
+    if lower == ' ' {
+        return Some(upper);
+    }
+    if (layout & SM_VERTICAL_SMUSH) == 0 {
+        return None;
+    }
+    if (layout & SM_VERTICAL_RULES) == 0 {
+        return Some(lower);
+    }
+
+    if (layout & SM_VERTICAL_EQUAL) != 0 && upper == lower {
+        return Some(upper);
+    }
+    if (layout & SM_VERTICAL_LOWLINE) != 0 {
+        if let Some(smushed) = smush_lowline(upper, lower) {
+            return Some(smushed);
+        }
+    }
+    if (layout & SM_VERTICAL_HIERARCHY) != 0 {
+        if let Some(smushed) = smush_hierarchy(upper, lower) {
+            return Some(smushed);
+        }
+    }
+    if (layout & SM_HORIZONTAL_LINE) != 0 && matches!((upper, lower), ('-', '_') | ('_', '-')) {
+        return Some('=');
+    }
+    if (layout & SM_VERTICAL_LINE) != 0 && upper == '|' && lower == '|' {
+        return Some('|');
+    }
+
+    None
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VerticalFit {
+    /// the rows don't collide, the next row can be tried as well
+    Valid,
+    /// the rows collide and are smushed, no further row can be overlapped
+    End,
+    Invalid,
+}
+
+fn vertical_fit(layout: i32, upper: &str, lower: &str) -> VerticalFit {
+    let upper: Vec<char> = upper.chars().collect();
+    let lower: Vec<char> = lower.chars().collect();
+
+    let mut fit = VerticalFit::Valid;
+    for i in 0..upper.len().max(lower.len()) {
+        let upper_ch = upper.get(i).copied().unwrap_or(' ');
+        let lower_ch = lower.get(i).copied().unwrap_or(' ');
+        if upper_ch == ' ' || lower_ch == ' ' {
+            continue;
+        }
+        if (layout & SM_VERTICAL_LINE) != 0 && upper_ch == '|' && lower_ch == '|' {
+            continue;
+        }
+        if vertical_smush_chars(layout, upper_ch, lower_ch).is_none() {
+            return VerticalFit::Invalid;
+        }
+        fit = VerticalFit::End;
+    }
+
+    fit
+}
+
+fn is_blank_block(block: &[String]) -> bool {
+    block.iter().all(|line| line.trim().is_empty())
+}
+
+/// how many rows of `lower` can be moved up into the last `max_overlap` rows of `upper`
+fn vertical_overlap(layout: i32, upper: &[String], lower: &[String], max_overlap: usize) -> usize {
+    if (layout & (SM_VERTICAL_KERN | SM_VERTICAL_SMUSH)) == 0 {
+        return 0;
+    }
+
+    let mut overlap = 0;
+    for amount in 1..=max_overlap.min(lower.len()) {
+        let upper_rows = &upper[upper.len() - amount..];
+        let mut fit = VerticalFit::Valid;
+        for (upper_row, lower_row) in upper_rows.iter().zip(lower) {
+            match vertical_fit(layout, upper_row, lower_row) {
+                VerticalFit::Invalid => return overlap,
+                VerticalFit::End => fit = VerticalFit::End,
+                VerticalFit::Valid => {}
+            }
+        }
+
+        overlap = amount;
+        if fit == VerticalFit::End {
+            break;
+        }
+    }
+
+    overlap
+}
+
+fn vertical_smush_rows(layout: i32, upper: &str, lower: &str) -> String {
+    let upper: Vec<char> = upper.chars().collect();
+    let lower: Vec<char> = lower.chars().collect();
+
+    (0..upper.len().max(lower.len()))
+        .map(|i| {
+            let upper_ch = upper.get(i).copied().unwrap_or(' ');
+            let lower_ch = lower.get(i).copied().unwrap_or(' ');
+            vertical_smush_chars(layout, upper_ch, lower_ch).unwrap_or(lower_ch)
+        })
+        .collect()
+}
+
+/// stack the rendered rows of text on top of each other, fitting or smushing them vertically
+/// as the layout asks. Blank rows of text are kept as they are, so empty input lines still
+/// produce a gap.
+fn stack_blocks(layout: i32, blocks: Vec<Vec<String>>) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    let mut prev_block: Option<Vec<String>> = None;
+
+    for block in blocks {
+        let overlap = match &prev_block {
+            Some(prev) if !is_blank_block(prev) && !is_blank_block(&block) => {
+                vertical_overlap(layout, &lines, &block, prev.len())
+            }
+            _ => 0,
+        };
+
+        let start = lines.len() - overlap;
+        for (line, lower) in lines[start..].iter_mut().zip(&block) {
+            *line = vertical_smush_rows(layout, line, lower);
+        }
+        lines.extend(block[overlap..].iter().cloned());
+        prev_block = Some(block);
+    }
+
+    lines
+}
+
+fn is_space(character: &FIGcharacter) -> bool {
+    character.code == ' ' as u32
+}
+
+fn trim_trailing_spaces<'a>(characters: &[&'a FIGcharacter]) -> Vec<&'a FIGcharacter> {
+    let end = characters
+        .iter()
+        .rposition(|character| !is_space(character))
+        .map_or(0, |idx| idx + 1);
+    characters[..end].to_vec()
+}
+
+/// split one row of text into several rows which fit into `width` columns, breaking at word
+/// boundaries first and between characters when a single word is too wide.
+///
+/// Like figlet's `addchar`, every FIGcharacter is measured against the row rendered so far, the
+/// row is only rendered again from its start after a break.
+fn wrap_characters<'a>(
+    header_line: &HeaderLine,
+    options: &RenderOptions,
+    characters: &[&'a FIGcharacter],
+    width: usize,
+) -> Vec<Vec<&'a FIGcharacter>> {
+    let mut rows = vec![];
+    let mut current: Vec<&FIGcharacter> = vec![];
+    let mut renderer = Renderer::new(header_line, options);
+
+    for character in characters {
+        if current.is_empty() && !rows.is_empty() && is_space(character) {
+            continue;
+        }
+
+        loop {
+            if current.is_empty() || renderer.width_with(character) <= width {
+                renderer.add(character);
+                current.push(character);
+                break;
+            }
+
+            if is_space(character) {
+                rows.push(trim_trailing_spaces(&current));
+                current.clear();
+                renderer = Renderer::new(header_line, options);
+                break;
+            }
+
+            let break_at = current
+                .iter()
+                .rposition(|ch| is_space(ch))
+                .filter(|idx| !trim_trailing_spaces(&current[..*idx]).is_empty());
+            match break_at {
+                Some(idx) => {
+                    rows.push(trim_trailing_spaces(&current[..idx]));
+                    current.drain(..=idx);
+                    let leading = current.iter().take_while(|ch| is_space(ch)).count();
+                    current.drain(..leading);
+                }
+                None => rows.push(core::mem::take(&mut current)),
+            }
+
+            renderer = Renderer::new(header_line, options);
+            for character in &current {
+                renderer.add(character);
+            }
+        }
+    }
+
+    if !current.is_empty() || rows.is_empty() {
+        rows.push(current);
+    }
+
+    rows
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeaderLine {
     pub header_line: String,
     pub signature: String,
@@ -281,31 +1218,32 @@ pub struct HeaderLine {
 impl HeaderLine {
     fn extract_signature_with_hardblank(
         signature_with_hardblank: &str,
-    ) -> Result<(String, char), String> {
-        if signature_with_hardblank.len() < 6 {
-            Err("can't get signature with hardblank from first line of font".to_string())
-        } else {
-            let hardblank_index = signature_with_hardblank.len() - 1;
-            let signature = &signature_with_hardblank[..hardblank_index];
-            let hardblank = signature_with_hardblank[hardblank_index..]
-                .chars()
-                .next()
-                .unwrap();
-
-            Ok((String::from(signature), hardblank))
+    ) -> Result<(String, char), Error> {
+        // the hardblank is the last character, which may take more than one byte
+        match signature_with_hardblank.char_indices().next_back() {
+            Some((hardblank_index, hardblank)) if hardblank_index >= 5 => Ok((
+                String::from(&signature_with_hardblank[..hardblank_index]),
+                hardblank,
+            )),
+            _ => Err(Error::Header(
+                "can't get signature with hardblank from first line of font".to_string(),
+            )),
         }
     }
 
-    fn extract_required_info(infos: &[&str], index: usize, field: &str) -> Result<i32, String> {
+    fn extract_required_info(infos: &[&str], index: usize, field: &str) -> Result<i32, Error> {
         let val = infos.get(index).ok_or_else(|| {
-            format!(
+            Error::Header(format!(
                 "can't get field:{field} index:{index} from {}",
                 infos.join(",")
-            )
+            ))
         })?;
 
-        val.parse()
-            .map_err(|_| format!("can't parse required field:{field} of {val} to i32"))
+        val.parse().map_err(|_| {
+            Error::Header(format!(
+                "can't parse required field:{field} of {val} to i32"
+            ))
+        })
     }
 
     fn extract_optional_info(infos: &[&str], index: usize) -> Option<i32> {
@@ -321,19 +1259,55 @@ impl HeaderLine {
         }
     }
 
+    /// the vertical fitting and smushing bits of the layout, which only `full_layout` can set
+    pub(crate) fn vertical_layout(&self) -> i32 {
+        self.full_layout.map_or(0, |layout| {
+            layout & (SM_VERTICAL_RULES | SM_VERTICAL_KERN | SM_VERTICAL_SMUSH)
+        })
+    }
+
     fn is_right_to_left(&self) -> bool {
         self.print_direction == Some(1)
     }
+
+    /// the horizontal layout the font asks for, never `Layout::Default`
+    pub fn layout(&self) -> Layout {
+        let layout = self.effective_layout();
+        let rules = layout & SmushRules::ALL.bits();
+        if layout & SM_SMUSH == 0 {
+            if layout & SM_KERN == 0 {
+                Layout::FullWidth
+            } else {
+                Layout::Fitting
+            }
+        } else if rules == 0 {
+            Layout::UniversalSmushing
+        } else {
+            Layout::ControlledSmushing(SmushRules(rules))
+        }
+    }
+
+    /// the print direction the font asks for, never `PrintDirection::Default`
+    pub fn direction(&self) -> PrintDirection {
+        if self.is_right_to_left() {
+            PrintDirection::RightToLeft
+        } else {
+            PrintDirection::LeftToRight
+        }
+    }
 }
 
 impl TryFrom<&str> for HeaderLine {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(header_line: &str) -> Result<Self, Self::Error> {
         let infos: Vec<&str> = header_line.split_whitespace().collect();
 
         if infos.len() < 6 {
-            return Err("headerline is illegal".to_string());
+            return Err(Error::Header(format!(
+                "expected at least 6 fields, found {}",
+                infos.len()
+            )));
         }
 
         let (signature, hardblank) =
@@ -356,11 +1330,48 @@ impl TryFrom<&str> for HeaderLine {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FIGcharacter {
     pub code: u32,
     pub characters: Vec<String>,
     pub width: u32,
     pub height: u32,
+    /// the description after the code on the codetag line, `None` for FIGcharacters without
+    /// one. Required FIGcharacters are written without a codetag line, so they lose their name.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: Option<String>,
+}
+
+impl FIGcharacter {
+    /// whether `name` is the name of the FIGcharacter, ignoring ASCII case. Toilet fonts put the
+    /// character itself before its Unicode name, like `À LATIN CAPITAL LETTER A WITH GRAVE`, so
+    /// the name without that character matches as well.
+    fn has_name(&self, name: &str) -> bool {
+        let Some(own) = &self.name else {
+            return false;
+        };
+        let name = name.trim();
+        if own.eq_ignore_ascii_case(name) {
+            return true;
+        }
+
+        let mut chars = own.chars();
+        chars.next() == char::from_u32(self.code)
+            && chars.as_str().trim_start().eq_ignore_ascii_case(name)
+    }
+}
+
+/// the first FIGcharacter with the given name, see `FIGcharacter::name`
+pub(crate) fn character_by_name<'a>(
+    fonts: &'a BTreeMap<u32, FIGcharacter>,
+    name: &str,
+) -> Option<&'a FIGcharacter> {
+    fonts.values().find(|character| character.has_name(name))
+}
+
+/// the name of the FIGcharacter for `code`
+pub(crate) fn character_name(fonts: &BTreeMap<u32, FIGcharacter>, code: u32) -> Option<&str> {
+    fonts.get(&code)?.name.as_deref()
 }
 
 impl fmt::Display for FIGcharacter {
@@ -369,9 +1380,17 @@ impl fmt::Display for FIGcharacter {
     }
 }
 
+/// the rendered result, one or more rows of text stacked on top of each other
+///
+/// With the `serde` feature a FIGure can be serialized, and deserializes into an owned
+/// `FIGure<'static>`, see `into_owned`.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FIGure<'a> {
-    pub characters: Vec<&'a FIGcharacter>,
+    /// every FIGcharacter of the message, across all rows of text. Placeholders for missing
+    /// characters are owned, everything else is borrowed from the font.
+    pub characters: Vec<Cow<'a, FIGcharacter>>,
+    /// height of a single row of text
     pub height: u32,
     lines: Vec<String>,
 }
@@ -384,6 +1403,19 @@ impl<'a> FIGure<'a> {
     pub fn as_str(&self) -> String {
         self.to_string()
     }
+
+    /// a FIGure which owns its FIGcharacters, so it can outlive the font
+    pub fn into_owned(self) -> FIGure<'static> {
+        FIGure {
+            characters: self
+                .characters
+                .into_iter()
+                .map(|character| Cow::Owned(character.into_owned()))
+                .collect(),
+            height: self.height,
+            lines: self.lines,
+        }
+    }
 }
 
 impl<'a> fmt::Display for FIGure<'a> {
@@ -401,55 +1433,88 @@ impl<'a> fmt::Display for FIGure<'a> {
 
 struct Renderer<'a> {
     header_line: &'a HeaderLine,
+    layout: i32,
+    right_to_left: bool,
     prev_char_width: usize,
     cur_char_width: usize,
     max_smush: usize,
+    /// the rows rendered so far, hardblanks are still in place
+    buffer: Vec<Vec<char>>,
 }
 
 impl<'a> Renderer<'a> {
-    fn new(header_line: &'a HeaderLine, _fonts: &'a HashMap<u32, FIGcharacter>) -> Self {
+    fn new(header_line: &'a HeaderLine, options: &RenderOptions) -> Self {
         Self {
             header_line,
+            layout: options.layout.horizontal_layout(header_line),
+            right_to_left: options.direction.is_right_to_left(header_line),
             prev_char_width: 0,
             cur_char_width: 0,
             max_smush: 0,
+            buffer: vec![vec![]; header_line.height.max(0) as usize],
         }
     }
 
     fn render(mut self, characters: &[&FIGcharacter]) -> Vec<String> {
-        let mut buffer = vec![String::new(); self.header_line.height as usize];
         for character in characters {
-            self.cur_char_width = character.width as usize;
-            self.max_smush = self.smush_amount(&buffer, character);
-
-            for (row, buffer_row) in buffer.iter_mut().enumerate() {
-                self.add_char_row_to_buffer_row(buffer_row, &character.characters[row]);
-            }
-
-            self.prev_char_width = self.cur_char_width;
+            self.add(character);
         }
 
-        buffer
+        let hardblank = self.header_line.hardblank;
+        self.buffer
             .into_iter()
-            .map(|line| line.replace(self.header_line.hardblank, " "))
+            .map(|row| {
+                row.into_iter()
+                    .map(|ch| if ch == hardblank { ' ' } else { ch })
+                    .collect()
+            })
             .collect()
     }
 
-    fn add_char_row_to_buffer_row(&self, buffer_row: &mut String, char_row: &str) {
-        let (mut left, right) = if self.header_line.is_right_to_left() {
-            (
-                char_row.chars().collect::<Vec<_>>(),
-                buffer_row.chars().collect::<Vec<_>>(),
-            )
+    /// append a FIGcharacter to the rows rendered so far
+    fn add(&mut self, character: &FIGcharacter) {
+        self.cur_char_width = character.width as usize;
+        self.max_smush = self.smush_amount(character);
+
+        for row in 0..self.buffer.len() {
+            let char_row: Vec<char> = character.characters[row].chars().collect();
+            self.add_char_row_to_buffer_row(row, char_row);
+        }
+
+        self.prev_char_width = self.cur_char_width;
+    }
+
+    /// the width of the rendered rows if `character` was added, without adding it
+    fn width_with(&mut self, character: &FIGcharacter) -> usize {
+        self.cur_char_width = character.width as usize;
+        let max_smush = self.smush_amount(character);
+
+        self.buffer
+            .iter()
+            .zip(&character.characters)
+            .map(|(buffer_row, char_row)| {
+                let char_len = char_row.chars().count();
+                if self.right_to_left {
+                    char_len + buffer_row.len().saturating_sub(max_smush)
+                } else {
+                    buffer_row.len() + char_len.saturating_sub(max_smush)
+                }
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn add_char_row_to_buffer_row(&mut self, row: usize, char_row: Vec<char>) {
+        let max_smush = self.max_smush;
+        let buffer_row = core::mem::take(&mut self.buffer[row]);
+        let (mut left, right) = if self.right_to_left {
+            (char_row, buffer_row)
         } else {
-            (
-                buffer_row.chars().collect::<Vec<_>>(),
-                char_row.chars().collect::<Vec<_>>(),
-            )
+            (buffer_row, char_row)
         };
 
-        for i in 0..self.max_smush {
-            let idx = left.len() as isize - self.max_smush as isize + i as isize;
+        for i in 0..max_smush {
+            let idx = left.len() as isize - max_smush as isize + i as isize;
             let left_ch = if idx >= 0 {
                 left.get(idx as usize).copied().unwrap_or('\0')
             } else {
@@ -463,31 +1528,29 @@ impl<'a> Renderer<'a> {
             }
         }
 
-        left.extend(right.into_iter().skip(self.max_smush));
-        *buffer_row = left.into_iter().collect();
+        left.extend(right.into_iter().skip(max_smush));
+        self.buffer[row] = left;
     }
 
-    fn smush_amount(&self, buffer: &[String], character: &FIGcharacter) -> usize {
-        let layout = self.header_line.effective_layout();
+    fn smush_amount(&self, character: &FIGcharacter) -> usize {
+        let layout = self.layout;
         if (layout & (SM_SMUSH | SM_KERN)) == 0 {
             return 0;
         }
 
         let mut max_smush = self.cur_char_width;
-        for (row, buffer_row) in buffer
-            .iter()
-            .enumerate()
-            .take(self.header_line.height as usize)
-        {
-            let (line_left, line_right) = if self.header_line.is_right_to_left() {
-                (&character.characters[row], buffer_row)
+        for (row, buffer_row) in self.buffer.iter().enumerate() {
+            if self.right_to_left {
+                max_smush = max_smush.min(buffer_row.len());
+            }
+
+            let char_row: Vec<char> = character.characters[row].chars().collect();
+            let (left_chars, right_chars) = if self.right_to_left {
+                (&char_row, buffer_row)
             } else {
-                (buffer_row, &character.characters[row])
+                (buffer_row, &char_row)
             };
 
-            let left_chars: Vec<char> = line_left.chars().collect();
-            let right_chars: Vec<char> = line_right.chars().collect();
-
             let trimmed_left_len = left_chars
                 .iter()
                 .rposition(|ch| *ch != ' ')
@@ -534,7 +1597,7 @@ impl<'a> Renderer<'a> {
             return None;
         }
 
-        let layout = self.header_line.effective_layout();
+        let layout = self.layout;
         if (layout & SM_SMUSH) == 0 {
             return None;
         }
@@ -547,7 +1610,7 @@ impl<'a> Renderer<'a> {
                 return Some(left);
             }
 
-            return if self.header_line.is_right_to_left() {
+            return if self.right_to_left {
                 Some(left)
             } else {
                 Some(right)
@@ -567,27 +1630,13 @@ impl<'a> Renderer<'a> {
             return Some(left);
         }
         if (layout & SM_LOWLINE) != 0 {
-            if left == '_' && "|/\\[]{}()<>".contains(right) {
-                return Some(right);
-            }
-            if right == '_' && "|/\\[]{}()<>".contains(left) {
-                return Some(left);
+            if let Some(smushed) = smush_lowline(left, right) {
+                return Some(smushed);
             }
         }
         if (layout & SM_HIERARCHY) != 0 {
-            for (a, b) in [
-                ("|", "/\\[]{}()<>"),
-                ("/\\", "[]{}()<>"),
-                ("[]", "{}()<>"),
-                ("{}", "()<>"),
-                ("()", "<>"),
-            ] {
-                if a.contains(left) && b.contains(right) {
-                    return Some(right);
-                }
-                if a.contains(right) && b.contains(left) {
-                    return Some(left);
-                }
+            if let Some(smushed) = smush_hierarchy(left, right) {
+                return Some(smushed);
             }
         }
         if (layout & SM_PAIR) != 0 {
@@ -0,0 +1,176 @@
+use crate::figlet::FIGlet;
+use crate::font::Font;
+use crate::shared::{
+    render, FIGcharacter, FIGure, HeaderLine, RenderBuilder, RenderError, RenderOptions,
+};
+use crate::toilet::Toilet;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// a primary font with fallback fonts for the characters it doesn't define
+///
+/// Every character is taken from the first font of the stack which defines it, and the whole
+/// message is rendered in one go with the layout, print direction and hardblank of the primary
+/// font. Fonts of different heights are aligned on their baselines, so the stack is as high as
+/// the tallest part above and the tallest part below the baseline together.
+///
+/// The FIGcharacters are copied into the stack when a font is added, the fonts themselves are
+/// not borrowed.
+///
+/// ```
+/// use figlet_rs::{FontStack, FIGlet, Toilet};
+///
+/// let slant = FIGlet::slant().unwrap();
+/// let future = Toilet::future().unwrap();
+/// let stack = FontStack::from_figlet(&slant).fallback_toilet(&future);
+///
+/// assert!(slant.convert("│").is_none());
+/// let figure = stack.convert("Rust │").unwrap();
+/// assert_eq!(6, figure.height);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FontStack {
+    header_line: HeaderLine,
+    fonts: BTreeMap<u32, FIGcharacter>,
+}
+
+impl FontStack {
+    /// a stack with a FIGlet font as the primary font
+    pub fn from_figlet(font: &FIGlet) -> FontStack {
+        FontStack::new(&font.header_line, &font.fonts)
+    }
+
+    /// a stack with a Toilet font as the primary font
+    pub fn from_toilet(font: &Toilet) -> FontStack {
+        FontStack::new(&font.header_line, &font.fonts)
+    }
+
+    /// a stack with a FIGlet or Toilet font as the primary font
+    pub fn from_font(font: &Font) -> FontStack {
+        FontStack::new(font.header_line(), font.fonts())
+    }
+
+    fn new(header_line: &HeaderLine, fonts: &BTreeMap<u32, FIGcharacter>) -> FontStack {
+        FontStack {
+            header_line: header_line.clone(),
+            fonts: BTreeMap::new(),
+        }
+        .fallback(header_line, fonts)
+    }
+
+    /// add a FIGlet font, which is tried after every font added before
+    pub fn fallback_figlet(self, font: &FIGlet) -> FontStack {
+        self.fallback(&font.header_line, &font.fonts)
+    }
+
+    /// add a Toilet font, which is tried after every font added before
+    pub fn fallback_toilet(self, font: &Toilet) -> FontStack {
+        self.fallback(&font.header_line, &font.fonts)
+    }
+
+    /// add a FIGlet or Toilet font, which is tried after every font added before
+    pub fn fallback_font(self, font: &Font) -> FontStack {
+        self.fallback(font.header_line(), font.fonts())
+    }
+
+    fn fallback(
+        mut self,
+        header_line: &HeaderLine,
+        fonts: &BTreeMap<u32, FIGcharacter>,
+    ) -> FontStack {
+        let (ascent, descent) = ascent_and_descent(header_line);
+        let (stack_ascent, stack_descent) = if self.fonts.is_empty() {
+            (ascent, descent)
+        } else {
+            ascent_and_descent(&self.header_line)
+        };
+        let new_ascent = stack_ascent.max(ascent);
+        let new_descent = stack_descent.max(descent);
+
+        if (new_ascent, new_descent) != (stack_ascent, stack_descent) {
+            for character in self.fonts.values_mut() {
+                pad_character(
+                    character,
+                    new_ascent - stack_ascent,
+                    new_descent - stack_descent,
+                );
+            }
+        }
+
+        let hardblank = String::from(self.header_line.hardblank);
+        for (code, character) in fonts {
+            if self.fonts.contains_key(code) {
+                continue;
+            }
+
+            let mut character = character.clone();
+            if header_line.hardblank != self.header_line.hardblank {
+                for row in &mut character.characters {
+                    *row = row.replace(header_line.hardblank, &hardblank);
+                }
+            }
+            pad_character(&mut character, new_ascent - ascent, new_descent - descent);
+            self.fonts.insert(*code, character);
+        }
+
+        self.header_line.height = (new_ascent + new_descent) as i32;
+        self.header_line.baseline = new_ascent as i32;
+        self.header_line.max_length = self.header_line.max_length.max(header_line.max_length);
+        self
+    }
+
+    /// the header line of the primary font, with the height and baseline of the whole stack
+    pub fn header_line(&self) -> &HeaderLine {
+        &self.header_line
+    }
+
+    /// the FIGcharacter for `code` from the first font which defines it, padded to the height
+    /// of the stack
+    pub fn get(&self, code: u32) -> Option<&FIGcharacter> {
+        self.fonts.get(&code)
+    }
+
+    /// convert string literal to FIGure, every `\n` or `\r\n` starts a new row of text
+    pub fn convert(&self, message: &str) -> Option<FIGure<'_>> {
+        self.convert_with_options(message, &RenderOptions::default())
+            .ok()
+    }
+
+    /// start rendering with non-default options, see `RenderBuilder`
+    pub fn render(&self) -> RenderBuilder<'_> {
+        RenderBuilder::new(&self.header_line, &self.fonts)
+    }
+
+    /// convert string literal to FIGure with the given RenderOptions
+    pub fn convert_with_options(
+        &self,
+        message: &str,
+        options: &RenderOptions,
+    ) -> Result<FIGure<'_>, RenderError> {
+        render(&self.header_line, &self.fonts, message, options)
+    }
+}
+
+/// rows from the top down to the baseline, and rows below the baseline
+fn ascent_and_descent(header_line: &HeaderLine) -> (usize, usize) {
+    let height = header_line.height.max(0) as usize;
+    let baseline = (header_line.baseline.max(0) as usize).min(height);
+    (baseline, height - baseline)
+}
+
+/// add blank rows above and below a FIGcharacter
+fn pad_character(character: &mut FIGcharacter, top: usize, bottom: usize) {
+    if top == 0 && bottom == 0 {
+        return;
+    }
+
+    let blank = " ".repeat(character.width as usize);
+    let mut rows: Vec<String> = Vec::with_capacity(top + character.characters.len() + bottom);
+    rows.extend(core::iter::repeat(blank.clone()).take(top));
+    rows.append(&mut character.characters);
+    rows.extend(core::iter::repeat(blank).take(bottom));
+
+    character.height = rows.len() as u32;
+    character.characters = rows;
+}
@@ -1,60 +1,147 @@
+use crate::builtin::BuiltinFont;
+use crate::check::Diagnostic;
+use crate::encoding::Encoding;
+use crate::error::Error;
+use crate::metadata::FontMetadata;
+#[cfg(feature = "std")]
+use crate::shared::load_font_file;
+#[cfg(feature = "serde")]
+use crate::shared::UncheckedFont;
 use crate::shared::{
-    load_font_file, parse_font_bytes, parse_font_content, render, FIGcharacter, FIGure, FontData,
-    HeaderLine,
+    character_by_name, character_name, parse_font_bytes, parse_font_bytes_with_mode,
+    parse_font_content, parse_font_content_with_mode, render, FIGcharacter, FIGure, FontData,
+    HeaderLine, ParseMode, RenderBuilder, RenderError, RenderOptions,
 };
-use std::collections::HashMap;
+use crate::writer::write_font_content;
+#[cfg(feature = "std")]
+use crate::writer::{write_font_file, write_font_zip};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 /// Toilet font, which supports loading `.tlf` files, including zip-packaged fonts.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedFont"))]
 pub struct Toilet {
     pub header_line: HeaderLine,
     pub comments: String,
-    pub fonts: HashMap<u32, FIGcharacter>,
+    pub fonts: BTreeMap<u32, FIGcharacter>,
 }
 
 impl Toilet {
     /// generate Toilet font from string literal
-    pub fn from_content(contents: &str) -> Result<Toilet, String> {
+    pub fn from_content(contents: &str) -> Result<Toilet, Error> {
         Ok(parse_font_content(contents)?.into())
     }
 
-    /// generate Toilet font from specified file
-    pub fn from_file(fontname: &str) -> Result<Toilet, String> {
-        Ok(load_font_file(fontname)?.into())
+    /// generate Toilet font from string literal in the given mode, along with the warnings
+    pub fn from_content_with_mode(
+        contents: &str,
+        mode: ParseMode,
+    ) -> Result<(Toilet, Vec<Diagnostic>), Error> {
+        let (data, warnings) = parse_font_content_with_mode(contents, mode)?;
+        Ok((data.into(), warnings))
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Toilet, String> {
-        Ok(parse_font_bytes(bytes)?.into())
+    /// generate Toilet font from specified file, which must be UTF-8 encoded
+    #[cfg(feature = "std")]
+    pub fn from_file(fontname: &str) -> Result<Toilet, Error> {
+        Ok(load_font_file(fontname, Some(Encoding::Utf8))?.into())
+    }
+
+    /// generate Toilet font from the bytes of a plain or zip-packaged font, which must be UTF-8
+    /// encoded
+    pub fn from_bytes(bytes: &[u8]) -> Result<Toilet, Error> {
+        Ok(parse_font_bytes(bytes, Some(Encoding::Utf8))?.into())
+    }
+
+    /// generate Toilet font from bytes in the given mode, along with the warnings
+    pub fn from_bytes_with_mode(
+        bytes: &[u8],
+        mode: ParseMode,
+    ) -> Result<(Toilet, Vec<Diagnostic>), Error> {
+        let (data, warnings) = parse_font_bytes_with_mode(bytes, Some(Encoding::Utf8), mode)?;
+        Ok((data.into(), warnings))
     }
 
     /// the smblock Toilet font bundled with the crate
-    pub fn smblock() -> Result<Toilet, String> {
-        Toilet::from_bytes(include_bytes!("../resources/smblock.tlf"))
+    pub fn smblock() -> Result<Toilet, Error> {
+        Toilet::from_bytes(BuiltinFont::Smblock.bytes())
     }
 
-    /// the mono12 Toilet font bundled with the crate
-    pub fn mono12() -> Result<Toilet, String> {
-        Toilet::from_bytes(include_bytes!("../resources/mono12.tlf"))
+    /// the mono12 Toilet font bundled with the crate, which is zip-packaged and needs `std`
+    #[cfg(feature = "std")]
+    pub fn mono12() -> Result<Toilet, Error> {
+        Toilet::from_bytes(BuiltinFont::Mono12.bytes())
     }
 
     /// the future Toilet font bundled with the crate
-    pub fn future() -> Result<Toilet, String> {
-        Toilet::from_bytes(include_bytes!("../resources/future.tlf"))
+    pub fn future() -> Result<Toilet, Error> {
+        Toilet::from_bytes(BuiltinFont::Future.bytes())
     }
 
     /// the wideterm Toilet font bundled with the crate
-    pub fn wideterm() -> Result<Toilet, String> {
-        Toilet::from_bytes(include_bytes!("../resources/wideterm.tlf"))
+    pub fn wideterm() -> Result<Toilet, Error> {
+        Toilet::from_bytes(BuiltinFont::Wideterm.bytes())
+    }
+
+    /// the mono9 Toilet font bundled with the crate, which is zip-packaged and needs `std`
+    #[cfg(feature = "std")]
+    pub fn mono9() -> Result<Toilet, Error> {
+        Toilet::from_bytes(BuiltinFont::Mono9.bytes())
+    }
+
+    /// credits, license and layout of the font, see `FontMetadata`
+    pub fn metadata(&self) -> FontMetadata {
+        FontMetadata::new(&self.header_line, &self.comments)
+    }
+
+    /// the FIGcharacter with the given codetag name, e.g. `LATIN CAPITAL LETTER A WITH DIAERESIS`
+    pub fn character_by_name(&self, name: &str) -> Option<&FIGcharacter> {
+        character_by_name(&self.fonts, name)
+    }
+
+    /// the codetag name of the FIGcharacter for `code`
+    pub fn character_name(&self, code: u32) -> Option<&str> {
+        character_name(&self.fonts, code)
     }
 
-    /// the mono9 Toilet font bundled with the crate
-    pub fn mono9() -> Result<Toilet, String> {
-        Toilet::from_bytes(include_bytes!("../resources/mono9.tlf"))
+    /// serialize the font to the contents of a `.tlf` file
+    pub fn to_content(&self) -> String {
+        write_font_content(&self.header_line, &self.comments, &self.fonts)
     }
 
-    /// convert string literal to FIGure
+    /// serialize the font to a zip archive holding the single file `file_name`
+    #[cfg(feature = "std")]
+    pub fn to_zip_bytes(&self, file_name: &str) -> Result<Vec<u8>, Error> {
+        write_font_zip(&self.to_content(), file_name)
+    }
+
+    /// write the font to specified file, zip-packaged when the name ends with `.zip`
+    #[cfg(feature = "std")]
+    pub fn write_file(&self, fontname: &str) -> Result<(), Error> {
+        write_font_file(&self.to_content(), fontname)
+    }
+
+    /// convert string literal to FIGure, every `\n` or `\r\n` starts a new row of text
     pub fn convert(&self, message: &str) -> Option<FIGure<'_>> {
-        render(&self.header_line, &self.fonts, message)
+        self.convert_with_options(message, &RenderOptions::default())
+            .ok()
+    }
+
+    /// start rendering with non-default options, see `RenderBuilder`
+    pub fn render(&self) -> RenderBuilder<'_> {
+        RenderBuilder::new(&self.header_line, &self.fonts)
+    }
+
+    /// convert string literal to FIGure with the given RenderOptions
+    pub fn convert_with_options(
+        &self,
+        message: &str,
+        options: &RenderOptions,
+    ) -> Result<FIGure<'_>, RenderError> {
+        render(&self.header_line, &self.fonts, message, options)
     }
 }
 
@@ -67,3 +154,13 @@ impl From<FontData> for Toilet {
         }
     }
 }
+
+/// a deserialized font is checked before it can be rendered
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedFont> for Toilet {
+    type Error = Error;
+
+    fn try_from(font: UncheckedFont) -> Result<Self, Self::Error> {
+        Ok(FontData::try_from(font)?.into())
+    }
+}
@@ -0,0 +1,169 @@
+#[cfg(feature = "std")]
+use crate::error::Error;
+use crate::shared::{FIGcharacter, HeaderLine, REQUIRED_CODES};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::io::{Cursor, Write};
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "std")]
+use zip::write::FileOptions;
+#[cfg(feature = "std")]
+use zip::ZipWriter;
+
+/// endmarks tried for a FIGcharacter, the first one which is neither the hardblank nor ends one
+/// of its rows is used
+const ENDMARKS: [char; 4] = ['@', '#', '$', '%'];
+
+/// write a font in the FIGfont 2.2 format, which `parse_font_content` reads back
+///
+/// The comment count and the codetag count of the header line are regenerated from what is
+/// written, the other fields are taken from `header_line`.
+pub(crate) fn write_font_content(
+    header_line: &HeaderLine,
+    comments: &str,
+    fonts: &BTreeMap<u32, FIGcharacter>,
+) -> String {
+    let codetags = codetag_characters(fonts);
+
+    // a missing required FIGcharacter is written as an empty one, unless nothing comes after it
+    let required_count = if codetags.is_empty() {
+        REQUIRED_CODES
+            .iter()
+            .rposition(|code| fonts.contains_key(code))
+            .map_or(0, |index| index + 1)
+    } else {
+        REQUIRED_CODES.len()
+    };
+
+    let comment_lines: Vec<&str> = if comments.is_empty() {
+        vec![]
+    } else {
+        comments.split('\n').collect()
+    };
+
+    let mut contents = write_header_line(header_line, comment_lines.len(), codetags.len());
+    contents.push('\n');
+    for line in comment_lines {
+        contents.push_str(line);
+        contents.push('\n');
+    }
+
+    let height = header_line.height.max(0) as usize;
+    let hardblank = header_line.hardblank;
+    for code in &REQUIRED_CODES[..required_count] {
+        match fonts.get(code) {
+            Some(font) => write_character(&mut contents, &font.characters, height, hardblank),
+            None => write_character(&mut contents, &[], height, hardblank),
+        }
+    }
+
+    for font in codetags {
+        contents.push_str(&font.code.to_string());
+        if let Some(name) = &font.name {
+            contents.push_str("  ");
+            contents.push_str(name);
+        }
+        contents.push('\n');
+        write_character(&mut contents, &font.characters, height, hardblank);
+    }
+
+    contents
+}
+
+/// the FIGcharacters written as codetags, in the order they are written
+///
+/// A named required FIGcharacter is written again as a codetag to keep its name, FIGfont 2.2
+/// allows a codetag to redefine a required code.
+pub(crate) fn codetag_characters(fonts: &BTreeMap<u32, FIGcharacter>) -> Vec<&FIGcharacter> {
+    let mut codetags: Vec<&FIGcharacter> = fonts
+        .values()
+        .filter(|font| !REQUIRED_CODES.contains(&font.code) || font.name.is_some())
+        .collect();
+    codetags.sort_by_key(|font| font.code);
+    codetags
+}
+
+/// write a font into a zip archive holding the single file `file_name`, the way packaged figlet
+/// and toilet fonts are distributed
+#[cfg(feature = "std")]
+pub(crate) fn write_font_zip(contents: &str, file_name: &str) -> Result<Vec<u8>, Error> {
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    zip.start_file(file_name, FileOptions::default())?;
+    zip.write_all(contents.as_bytes())?;
+    Ok(zip.finish()?.into_inner())
+}
+
+/// write a font to `filename`, zip-packaged when the name ends with `.zip`
+#[cfg(feature = "std")]
+pub(crate) fn write_font_file(contents: &str, filename: &str) -> Result<(), Error> {
+    match filename.strip_suffix(".zip") {
+        Some(inner) => {
+            let file_name = Path::new(inner)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            fs::write(filename, write_font_zip(contents, &file_name)?)?;
+        }
+        None => fs::write(filename, contents)?,
+    }
+
+    Ok(())
+}
+
+/// the header line of a font with the given number of comment lines and codetags
+pub(crate) fn write_header_line(
+    header_line: &HeaderLine,
+    comment_lines: usize,
+    codetag_count: usize,
+) -> String {
+    let mut fields = vec![
+        format!("{}{}", header_line.signature, header_line.hardblank),
+        header_line.height.to_string(),
+        header_line.baseline.to_string(),
+        header_line.max_length.to_string(),
+        header_line.old_layout.to_string(),
+        comment_lines.to_string(),
+    ];
+
+    // later fields can only be written together with the ones before them
+    let has_codetag_count = header_line.codetag_count.is_some() || codetag_count > 0;
+    let has_full_layout = header_line.full_layout.is_some() || has_codetag_count;
+    if header_line.print_direction.is_some() || has_full_layout {
+        fields.push(header_line.print_direction.unwrap_or(0).to_string());
+    }
+    if has_full_layout {
+        let full_layout = header_line
+            .full_layout
+            .unwrap_or_else(|| header_line.effective_layout());
+        fields.push(full_layout.to_string());
+    }
+    if has_codetag_count {
+        fields.push(codetag_count.to_string());
+    }
+
+    fields.join(" ")
+}
+
+fn write_character(contents: &mut String, rows: &[String], height: usize, hardblank: char) {
+    let endmark = ENDMARKS
+        .iter()
+        .copied()
+        .filter(|endmark| *endmark != hardblank)
+        .find(|endmark| !rows.iter().any(|row| row.ends_with(*endmark)))
+        .unwrap_or(ENDMARKS[0]);
+
+    for i in 0..height {
+        contents.push_str(rows.get(i).map_or("", String::as_str));
+        contents.push(endmark);
+        if i == height - 1 && height != 1 {
+            contents.push(endmark);
+        }
+        contents.push('\n');
+    }
+}